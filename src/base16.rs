@@ -0,0 +1,97 @@
+use crate::color::{Hsl, Palette, Rgb};
+
+pub const NAMES: [&str; 24] = [
+	"base00", "base01", "base02", "base03", "base04", "base05", "base06", "base07",
+	"base08", "base09", "base0A", "base0B", "base0C", "base0D", "base0E", "base0F",
+	"base10", "base11", "base12", "base13", "base14", "base15", "base16", "base17",
+];
+
+// Position of base00-base05 between background and foreground.
+const RAMP: [f64; 6] = [0.0, 0.08, 0.16, 0.42, 0.7, 1.0];
+
+#[derive(Clone, Copy)]
+pub enum System {
+	Base16,
+	Base24,
+}
+
+impl System {
+	pub fn name(self) -> &'static str {
+		match self {
+			System::Base16 => "base16",
+			System::Base24 => "base24",
+		}
+	}
+
+	fn len(self) -> usize {
+		match self {
+			System::Base16 => 16,
+			System::Base24 => 24,
+		}
+	}
+}
+
+pub fn base24(palette: &Palette) -> [Rgb; 24] {
+	let c = &palette.colors;
+	let bg = palette.background();
+	let fg = palette.foreground();
+	let (beyond_fg, beyond_bg) = if palette.is_light() {
+		(Rgb::new(0, 0, 0), Rgb::new(255, 255, 255))
+	} else {
+		(Rgb::new(255, 255, 255), Rgb::new(0, 0, 0))
+	};
+
+	let orange = {
+		let red = c[1].to_hsl();
+		let yellow = c[3].to_hsl();
+		let mut diff = yellow.h - red.h;
+		if diff > 180.0 {
+			diff -= 360.0;
+		} else if diff < -180.0 {
+			diff += 360.0;
+		}
+		let h = (red.h + diff / 2.0).rem_euclid(360.0);
+		Hsl::new(h, (red.s + yellow.s) / 2.0, (red.l + yellow.l) / 2.0)
+	};
+	let brown = Hsl::new(orange.h, orange.s * 0.6, orange.l * 0.65).to_rgb();
+
+	[
+		bg.mix(fg, RAMP[0]),
+		bg.mix(fg, RAMP[1]),
+		bg.mix(fg, RAMP[2]),
+		bg.mix(fg, RAMP[3]),
+		bg.mix(fg, RAMP[4]),
+		bg.mix(fg, RAMP[5]),
+		fg.mix(beyond_fg, 0.4),
+		fg.mix(beyond_fg, 0.75),
+		c[1],
+		orange.to_rgb(),
+		c[3],
+		c[2],
+		c[6],
+		c[4],
+		c[5],
+		brown,
+		bg.mix(beyond_bg, 0.25),
+		bg.mix(beyond_bg, 0.5),
+		c[9],
+		c[11],
+		c[10],
+		c[14],
+		c[12],
+		c[13],
+	]
+}
+
+pub fn to_yaml(palette: &Palette, system: System) -> String {
+	let colors = base24(palette);
+	let mut out = format!(
+		"system: \"{}\"\nname: \"Tinte\"\nauthor: \"tinte\"\nvariant: \"{}\"\npalette:\n",
+		system.name(),
+		if palette.is_light() { "light" } else { "dark" },
+	);
+	for (name, color) in NAMES.iter().zip(colors).take(system.len()) {
+		out.push_str(&format!("  {}: \"{}\"\n", name, color.to_hex()));
+	}
+	out
+}
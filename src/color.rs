@@ -1,5 +1,11 @@
 use std::fmt;
 
+const TEXT_CONTRAST: f64 = 4.5;
+const UI_CONTRAST: f64 = 3.0;
+const SURFACE_SAT: f64 = 0.1;
+// Each surface level moves this fraction of the background-foreground lightness gap.
+const SURFACE_STEP: f64 = 1.0 / 16.0;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rgb {
 	pub r: u8,
@@ -14,6 +20,13 @@ pub struct Hsl {
 	pub l: f64,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct Oklch {
+	pub l: f64,
+	pub c: f64,
+	pub h: f64,
+}
+
 impl Rgb {
 	pub fn new(r: u8, g: u8, b: u8) -> Self {
 		Self { r, g, b }
@@ -46,6 +59,81 @@ impl Rgb {
 		format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, alpha)
 	}
 
+	pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+		let t = t.clamp(0.0, 1.0);
+		let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+		Rgb::new(lerp(self.r, other.r), lerp(self.g, other.g), lerp(self.b, other.b))
+	}
+
+	// Amounts are HSL percentage points; negative values darken / desaturate.
+	pub fn lighten(self, amount: f64) -> Rgb {
+		let hsl = self.to_hsl();
+		Hsl::new(hsl.h, hsl.s, (hsl.l + amount / 100.0).clamp(0.0, 1.0)).to_rgb()
+	}
+
+	pub fn saturate(self, amount: f64) -> Rgb {
+		let hsl = self.to_hsl();
+		Hsl::new(hsl.h, (hsl.s + amount / 100.0).clamp(0.0, 1.0), hsl.l).to_rgb()
+	}
+
+	pub fn rotate(self, degrees: f64) -> Rgb {
+		let hsl = self.to_hsl();
+		Hsl::new((hsl.h + degrees).rem_euclid(360.0), hsl.s, hsl.l).to_rgb()
+	}
+
+	// `bg` or `fg`, whichever reads better on this color.
+	pub fn text_on(self, bg: Rgb, fg: Rgb) -> Rgb {
+		let text = if bg.contrast_ratio(self) >= fg.contrast_ratio(self) { bg } else { fg };
+		text.ensure_contrast(self, TEXT_CONTRAST)
+	}
+
+	pub fn luminance(self) -> f64 {
+		0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+	}
+
+	pub fn to_oklch(self) -> Oklch {
+		let (r, g, b) = (linear(self.r), linear(self.g), linear(self.b));
+
+		let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+		let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+		let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+		let lightness = 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s;
+		let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+		let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+
+		let c = (a * a + b * b).sqrt();
+		let h = if c < 1e-4 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+		Oklch { l: lightness, c, h }
+	}
+
+	pub fn contrast_ratio(self, other: Rgb) -> f64 {
+		let (a, b) = (self.luminance(), other.luminance());
+		(a.max(b) + 0.05) / (a.min(b) + 0.05)
+	}
+
+	// Moves lightness away from `against` until the WCAG contrast ratio is met.
+	pub fn ensure_contrast(self, against: Rgb, ratio: f64) -> Rgb {
+		if self.contrast_ratio(against) >= ratio {
+			return self;
+		}
+
+		let white = Rgb::new(255, 255, 255);
+		let black = Rgb::new(0, 0, 0);
+		let lighten = against.contrast_ratio(white) >= against.contrast_ratio(black);
+		let mut hsl = self.to_hsl();
+
+		while (lighten && hsl.l < 1.0) || (!lighten && hsl.l > 0.0) {
+			hsl.l = if lighten { (hsl.l + 0.01).min(1.0) } else { (hsl.l - 0.01).max(0.0) };
+			let candidate = hsl.to_rgb();
+			if candidate.contrast_ratio(against) >= ratio {
+				return candidate;
+			}
+		}
+
+		if lighten { white } else { black }
+	}
+
 	pub fn to_hsl(self) -> Hsl {
 		let r = self.r as f64 / 255.0;
 		let g = self.g as f64 / 255.0;
@@ -114,6 +202,52 @@ impl Hsl {
 	}
 }
 
+// A status role: the hue it should read as, how far a palette color may be
+// from it, and the ANSI slot that conventionally holds it.
+struct Role {
+	hue: f64,
+	tolerance: f64,
+	slot: usize,
+}
+
+const ERROR: Role = Role { hue: 0.0, tolerance: 30.0, slot: 1 };
+const WARNING: Role = Role { hue: 50.0, tolerance: 30.0, slot: 3 };
+const SUCCESS: Role = Role { hue: 120.0, tolerance: 65.0, slot: 2 };
+const INFO: Role = Role { hue: 215.0, tolerance: 45.0, slot: 4 };
+// Below this saturation a color has no usable hue.
+const ROLE_MIN_SAT: f64 = 0.15;
+
+// Picks the color for `role` by hue rather than by ANSI slot alone, since
+// generated palettes don't keep red in color1. Prefers the conventional slot,
+// then the closest of colors 1-6, and otherwise builds the hue from the
+// slot's saturation and lightness.
+fn role_color(colors: &[Rgb; 16], role: Role) -> Rgb {
+	let distance = |c: Rgb| {
+		let hsl = c.to_hsl();
+		let d = (hsl.h - role.hue).rem_euclid(360.0);
+		if hsl.s < ROLE_MIN_SAT { f64::INFINITY } else { d.min(360.0 - d) }
+	};
+
+	let slot = colors[role.slot];
+	if distance(slot) <= role.tolerance {
+		return slot;
+	}
+	let closest = colors[1..7]
+		.iter()
+		.copied()
+		.min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+		.filter(|c| distance(*c) <= role.tolerance);
+	closest.unwrap_or_else(|| {
+		let hsl = slot.to_hsl();
+		Hsl::new(role.hue, hsl.s.max(0.4), hsl.l).to_rgb()
+	})
+}
+
+fn linear(c: u8) -> f64 {
+	let c = c as f64 / 255.0;
+	if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
 fn hue_to_rgb(p: f64, q: f64, mut t: f64) -> f64 {
 	if t < 0.0 {
 		t += 1.0;
@@ -149,6 +283,18 @@ pub struct Palette {
 	pub surface: Rgb,
 	pub on_accent: Rgb,
 	pub on_surface: Rgb,
+	pub error: Rgb,
+	pub on_error: Rgb,
+	pub warning: Rgb,
+	pub success: Rgb,
+	pub info: Rgb,
+	pub border: Rgb,
+	pub selection_bg: Rgb,
+	pub selection_fg: Rgb,
+	pub cursor: Rgb,
+	pub cursor_text: Rgb,
+	pub muted: Rgb,
+	pub surfaces: [Rgb; 5],
 }
 
 impl Palette {
@@ -163,7 +309,49 @@ impl Palette {
 		on_accent: Rgb,
 		on_surface: Rgb,
 	) -> Self {
-		Self { colors, accent, accent_dim, accent_bright, secondary, surface, on_accent, on_surface }
+		let bg = colors[0];
+		let fg = colors[15];
+
+		let error = role_color(&colors, ERROR).ensure_contrast(bg, TEXT_CONTRAST);
+		let warning = role_color(&colors, WARNING).ensure_contrast(bg, TEXT_CONTRAST);
+		let success = role_color(&colors, SUCCESS).ensure_contrast(bg, TEXT_CONTRAST);
+		let info = role_color(&colors, INFO).ensure_contrast(bg, TEXT_CONTRAST);
+		let border = colors[8].ensure_contrast(bg, UI_CONTRAST);
+		let selection_bg = bg.mix(accent, 0.3);
+		let cursor = accent_bright.ensure_contrast(bg, UI_CONTRAST);
+		let muted = fg.mix(bg, 0.4).ensure_contrast(bg, TEXT_CONTRAST);
+
+		let accent_hsl = accent.to_hsl();
+		let bg_l = bg.to_hsl().l;
+		let step = (fg.to_hsl().l - bg_l) * SURFACE_STEP;
+		let mut surfaces = [bg; 5];
+		for (i, slot) in surfaces.iter_mut().enumerate() {
+			let l = (bg_l + step * (i + 1) as f64).clamp(0.0, 1.0);
+			*slot = Hsl::new(accent_hsl.h, accent_hsl.s.min(SURFACE_SAT), l).to_rgb();
+		}
+
+		Self {
+			colors,
+			accent,
+			accent_dim,
+			accent_bright,
+			secondary,
+			surface,
+			on_accent,
+			on_surface,
+			error,
+			on_error: error.text_on(bg, fg),
+			warning,
+			success,
+			info,
+			border,
+			selection_bg,
+			selection_fg: selection_bg.text_on(bg, fg),
+			cursor,
+			cursor_text: cursor.text_on(bg, fg),
+			muted,
+			surfaces,
+		}
 	}
 
 	pub fn background(&self) -> Rgb {
@@ -173,12 +361,81 @@ impl Palette {
 	pub fn foreground(&self) -> Rgb {
 		self.colors[15]
 	}
+
+	pub fn semantic(&self) -> Vec<(&'static str, Rgb)> {
+		vec![
+			("accent", self.accent),
+			("accent_dim", self.accent_dim),
+			("accent_bright", self.accent_bright),
+			("secondary", self.secondary),
+			("surface", self.surface),
+			("on_accent", self.on_accent),
+			("on_surface", self.on_surface),
+			("error", self.error),
+			("on_error", self.on_error),
+			("warning", self.warning),
+			("success", self.success),
+			("info", self.info),
+			("border", self.border),
+			("selection_bg", self.selection_bg),
+			("selection_fg", self.selection_fg),
+			("cursor", self.cursor),
+			("cursor_text", self.cursor_text),
+			("muted", self.muted),
+			("surface0", self.surfaces[0]),
+			("surface1", self.surfaces[1]),
+			("surface2", self.surfaces[2]),
+			("surface3", self.surfaces[3]),
+			("surface4", self.surfaces[4]),
+		]
+	}
+
+	pub fn is_light(&self) -> bool {
+		self.background().to_hsl().l > 0.5
+	}
+
+	// Colors 16-255: a 6x6x6 cube whose corners are background, the six ANSI
+	// hues and foreground, followed by a 24-step background-to-foreground ramp.
+	pub fn extended(&self) -> [Rgb; 240] {
+		let c = &self.colors;
+		let corners = [
+			[self.background(), c[1]],
+			[c[2], c[3]],
+			[c[4], c[5]],
+			[c[6], self.foreground()],
+		];
+		let lerp = |a: [f64; 3], b: [f64; 3], t: f64| {
+			[a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+		};
+		let channels = |c: Rgb| [c.r as f64, c.g as f64, c.b as f64];
+
+		let mut result = [Rgb::new(0, 0, 0); 240];
+		for r in 0..6 {
+			for g in 0..6 {
+				for b in 0..6 {
+					let (tr, tg, tb) = (r as f64 / 5.0, g as f64 / 5.0, b as f64 / 5.0);
+					let edge = |[lo, hi]: [Rgb; 2]| lerp(channels(lo), channels(hi), tr);
+					let near = lerp(edge(corners[0]), edge(corners[1]), tg);
+					let far = lerp(edge(corners[2]), edge(corners[3]), tg);
+					let [cr, cg, cb] = lerp(near, far, tb);
+					result[36 * r + 6 * g + b] =
+						Rgb::new(cr.round() as u8, cg.round() as u8, cb.round() as u8);
+				}
+			}
+		}
+
+		for i in 0..24 {
+			result[216 + i] = self.background().mix(self.foreground(), (i + 1) as f64 / 25.0);
+		}
+
+		result
+	}
 }
 
 impl Default for Palette {
 	fn default() -> Self {
-		Self {
-			colors: [
+		Self::new(
+			[
 				Rgb::from_hex("#1a1b26").unwrap(),
 				Rgb::from_hex("#f7768e").unwrap(),
 				Rgb::from_hex("#9ece6a").unwrap(),
@@ -196,13 +453,13 @@ impl Default for Palette {
 				Rgb::from_hex("#7dcfff").unwrap(),
 				Rgb::from_hex("#c0caf5").unwrap(),
 			],
-			accent: Rgb::from_hex("#7aa2f7").unwrap(),
-			accent_dim: Rgb::from_hex("#3d5179").unwrap(),
-			accent_bright: Rgb::from_hex("#a9c4fa").unwrap(),
-			secondary: Rgb::from_hex("#bb9af7").unwrap(),
-			surface: Rgb::from_hex("#24283b").unwrap(),
-			on_accent: Rgb::from_hex("#1a1b26").unwrap(),
-			on_surface: Rgb::from_hex("#c0caf5").unwrap(),
-		}
+			Rgb::from_hex("#7aa2f7").unwrap(),
+			Rgb::from_hex("#3d5179").unwrap(),
+			Rgb::from_hex("#a9c4fa").unwrap(),
+			Rgb::from_hex("#bb9af7").unwrap(),
+			Rgb::from_hex("#24283b").unwrap(),
+			Rgb::from_hex("#1a1b26").unwrap(),
+			Rgb::from_hex("#c0caf5").unwrap(),
+		)
 	}
 }
@@ -1,6 +1,8 @@
-use anyhow::{Context, Result};
+use crate::hook::Hook;
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
@@ -8,21 +10,65 @@ use std::path::PathBuf;
 pub struct Config {
 	#[serde(default)]
 	pub config: GlobalConfig,
+	// Kept in declaration order, which is the default processing order.
 	#[serde(default)]
-	pub templates: HashMap<String, TemplateConfig>,
+	pub templates: IndexMap<String, TemplateConfig>,
+	// Extra named colors for templates, e.g. `border = "accent | mix background 0.5"`.
+	#[serde(default)]
+	pub colors: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 pub struct GlobalConfig {
-	pub wallpaper_cmd: Option<String>,
-	pub post_hook: Option<String>,
+	pub wallpaper_cmd: Option<Hook>,
+	// Runs after rendering, before any output is written.
+	pub pre_hook: Option<Hook>,
+	pub post_hook: Option<Hook>,
+	// Gets the palette as JSON on stdin before anything is applied; failing
+	// rejects the palette.
+	pub on_palette: Option<Hook>,
+	// Runs last, after templates, hooks and the wallpaper.
+	pub on_complete: Option<Hook>,
+	pub templates_dir: Option<String>,
+	// Keep the previous version of each output under `$XDG_STATE_HOME/tinte/backups` (default on).
+	pub backup: Option<bool>,
+	// Restore all outputs of a run if writing one of them or a hook fails.
+	#[serde(default)]
+	pub rollback: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TemplateConfig {
 	pub input_path: String,
 	pub output_path: String,
-	pub post_hook: Option<String>,
+	pub pre_hook: Option<Hook>,
+	pub post_hook: Option<Hook>,
+	// Renders this template in the given mode regardless of `--mode`.
+	pub mode: Option<Mode>,
+	// Color replacements, e.g. `accent = "#ff8800"`; they win over `[colors]`.
+	#[serde(default)]
+	pub overrides: HashMap<String, String>,
+	#[serde(default)]
+	pub vars: HashMap<String, String>,
+	// Templates with a lower `order` are processed first (default 0).
+	#[serde(default)]
+	pub order: i64,
+	// Templates that must be processed (and their hooks run) before this one.
+	#[serde(default)]
+	pub after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+	Dark,
+	Light,
+}
+
+impl Mode {
+	pub fn is_light(self) -> bool {
+		self == Mode::Light
+	}
 }
 
 impl Config {
@@ -39,6 +85,38 @@ impl Config {
 		toml::from_str(&content)
 			.with_context(|| format!("Failed to parse config: {}", config_path.display()))
 	}
+
+	// Templates in processing order: by `order`, then declaration order,
+	// moving templates after the ones listed in their `after`.
+	pub fn ordered_templates(&self) -> Result<Vec<(&str, &TemplateConfig)>> {
+		for (name, template) in &self.templates {
+			for dep in &template.after {
+				if !self.templates.contains_key(dep) {
+					bail!("Template {} is ordered after unknown template {}", name, dep);
+				}
+			}
+		}
+
+		let mut pending: Vec<(&str, &TemplateConfig)> =
+			self.templates.iter().map(|(name, template)| (name.as_str(), template)).collect();
+		pending.sort_by_key(|(_, template)| template.order);
+
+		let mut done = HashSet::new();
+		let mut ordered = Vec::with_capacity(pending.len());
+		while !pending.is_empty() {
+			let Some(next) = pending
+				.iter()
+				.position(|(_, template)| template.after.iter().all(|dep| done.contains(dep.as_str())))
+			else {
+				let names: Vec<&str> = pending.iter().map(|(name, _)| *name).collect();
+				bail!("Circular `after` dependencies between templates: {}", names.join(", "));
+			};
+			let (name, template) = pending.remove(next);
+			done.insert(name);
+			ordered.push((name, template));
+		}
+		Ok(ordered)
+	}
 }
 
 fn default_config_path() -> PathBuf {
@@ -56,3 +134,75 @@ pub fn expand_path(path: &str) -> PathBuf {
 	}
 	PathBuf::from(path)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Templates named in `specs` as (name, order, after), in declaration order.
+	fn templates(specs: &[(&str, i64, &[&str])]) -> Config {
+		let mut src = String::new();
+		for (name, order, after) in specs {
+			let after: Vec<String> = after.iter().map(|dep| format!("{:?}", dep)).collect();
+			src.push_str(&format!(
+				"[templates.{}]\ninput_path = \"in\"\noutput_path = \"out\"\norder = {}\nafter = [{}]\n",
+				name,
+				order,
+				after.join(", ")
+			));
+		}
+		toml::from_str(&src).unwrap()
+	}
+
+	fn order(config: &Config) -> Vec<&str> {
+		config.ordered_templates().unwrap().into_iter().map(|(name, _)| name).collect()
+	}
+
+	#[test]
+	fn declaration_order_by_default() {
+		let config = templates(&[("c", 0, &[]), ("a", 0, &[]), ("b", 0, &[])]);
+		assert_eq!(order(&config), ["c", "a", "b"]);
+	}
+
+	#[test]
+	fn lower_order_first() {
+		let config = templates(&[("a", 1, &[]), ("b", -1, &[]), ("c", 0, &[]), ("d", 1, &[])]);
+		assert_eq!(order(&config), ["b", "c", "a", "d"]);
+	}
+
+	#[test]
+	fn after_moves_templates_back() {
+		let config = templates(&[("a", 0, &["c"]), ("b", 0, &[]), ("c", 0, &["b"]), ("d", 0, &[])]);
+		assert_eq!(order(&config), ["b", "c", "a", "d"]);
+	}
+
+	#[test]
+	fn after_overrides_order() {
+		let config = templates(&[("a", -5, &["b"]), ("b", 5, &[])]);
+		assert_eq!(order(&config), ["b", "a"]);
+	}
+
+	#[test]
+	fn after_cycles_are_errors() {
+		let config = templates(&[("a", 0, &["b"]), ("b", 0, &["a"]), ("c", 0, &[])]);
+		assert_eq!(
+			config.ordered_templates().unwrap_err().to_string(),
+			"Circular `after` dependencies between templates: a, b"
+		);
+
+		let config = templates(&[("a", 0, &["a"])]);
+		assert_eq!(
+			config.ordered_templates().unwrap_err().to_string(),
+			"Circular `after` dependencies between templates: a"
+		);
+	}
+
+	#[test]
+	fn after_unknown_template_is_an_error() {
+		let config = templates(&[("a", 0, &["missing"])]);
+		assert_eq!(
+			config.ordered_templates().unwrap_err().to_string(),
+			"Template a is ordered after unknown template missing"
+		);
+	}
+}
@@ -1,4 +1,5 @@
 use crate::color::{Hsl, Palette, Rgb};
+use crate::theme::Generator;
 use anyhow::{Context, Result};
 use std::collections::HashSet;
 use std::path::Path;
@@ -33,7 +34,7 @@ struct WeightedColor {
 	weight: u64,
 }
 
-pub fn extract_palette(image_path: &Path, light_mode: bool) -> Result<Palette> {
+pub fn extract_palette(image_path: &Path, light_mode: bool) -> Result<(Palette, Generator)> {
 	let resolved = image_path
 		.canonicalize()
 		.unwrap_or_else(|_| image_path.to_path_buf());
@@ -41,20 +42,59 @@ pub fn extract_palette(image_path: &Path, light_mode: bool) -> Result<Palette> {
 	let weighted = extract_with_imagemagick(&resolved)?;
 
 	if weighted.is_empty() {
-		return Ok(Palette::default());
+		return Ok((Palette::default(), Generator::Preset));
 	}
 
 	let hsl_colors: Vec<Hsl> = weighted.iter().map(|c| c.hsl).collect();
 
-	let palette = if is_monochrome(&hsl_colors) {
-		generate_monochrome_palette(&weighted, light_mode)
+	let result = if is_monochrome(&hsl_colors) {
+		(generate_monochrome_palette(&weighted, light_mode), Generator::Monochrome)
 	} else if has_low_diversity(&hsl_colors) {
-		generate_subtle_palette(&weighted, light_mode)
+		(generate_subtle_palette(&weighted, light_mode), Generator::Subtle)
 	} else {
-		generate_chromatic_palette(&weighted, light_mode)
+		(generate_chromatic_palette(&weighted, light_mode), Generator::Chromatic)
 	};
 
-	Ok(palette)
+	Ok(result)
+}
+
+pub fn generate_from_color(source: Rgb, light_mode: bool) -> Palette {
+	let hsl = source.to_hsl();
+	let bg_l = if light_mode { 0.92 } else { 0.08 };
+	let fg_l = if light_mode { 0.15 } else { 0.85 };
+	let color_l = if light_mode { 0.45 } else { 0.55 };
+	let bright_l = if light_mode { 0.35 } else { 0.65 };
+
+	let background = Hsl::new(hsl.h, hsl.s.min(0.15), bg_l).to_rgb();
+	let foreground = Hsl::new(hsl.h, hsl.s.min(0.1), fg_l).to_rgb();
+	let bright_black = Hsl::new(hsl.h, hsl.s.min(0.15), if light_mode { 0.75 } else { 0.25 }).to_rgb();
+
+	let hue_offsets = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+	let colors: Vec<Rgb> = hue_offsets
+		.iter()
+		.map(|offset| Hsl::new((hsl.h + offset) % 360.0, 0.6, color_l).to_rgb())
+		.collect();
+	let bright: Vec<Rgb> = hue_offsets
+		.iter()
+		.map(|offset| Hsl::new((hsl.h + offset) % 360.0, 0.7, bright_l).to_rgb())
+		.collect();
+
+	let accent = Hsl::new(hsl.h, hsl.s.max(0.5), color_l).to_rgb();
+	let accent_dim = Hsl::new(hsl.h, hsl.s.max(0.4), if light_mode { 0.6 } else { 0.35 }).to_rgb();
+	let accent_bright = Hsl::new(hsl.h, (hsl.s * 1.1).min(1.0), if light_mode { 0.35 } else { 0.7 }).to_rgb();
+	let secondary = Hsl::new((hsl.h + 180.0) % 360.0, 0.4, color_l).to_rgb();
+	let surface = Hsl::new(hsl.h, 0.1, if light_mode { 0.87 } else { 0.14 }).to_rgb();
+	let on_accent = if color_l > 0.5 {
+		Hsl::new(hsl.h, 0.15, 0.1).to_rgb()
+	} else {
+		Hsl::new(hsl.h, 0.05, 0.95).to_rgb()
+	};
+
+	Palette::new(
+		[background, colors[0], colors[1], colors[2], colors[3], colors[4], colors[5], foreground,
+		 bright_black, bright[0], bright[1], bright[2], bright[3], bright[4], bright[5], foreground],
+		accent, accent_dim, accent_bright, secondary, surface, on_accent, foreground,
+	)
 }
 
 fn extract_with_imagemagick(path: &Path) -> Result<Vec<WeightedColor>> {
@@ -0,0 +1,269 @@
+use crate::theme::{Source, Theme};
+use anyhow::{bail, Context, Result};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A post hook or wallpaper command. Configured as a string for `sh -c`, an
+// argument array run directly, or a table:
+//   post_hook = { command = ["pkill", "-SIGUSR2", "waybar"], timeout = 5, on_failure = "abort" }
+#[derive(Debug, Clone)]
+pub struct Hook {
+	pub command: CommandLine,
+	// How long to wait before killing the command.
+	pub timeout: Option<Duration>,
+	pub on_failure: OnFailure,
+	// Start the command without waiting for it.
+	pub background: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CommandLine {
+	Shell(String),
+	// Program and arguments, with `{name}` placeholders substituted in each
+	// argument, so values never pass through a shell.
+	Exec(Vec<String>),
+}
+
+impl fmt::Display for CommandLine {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CommandLine::Shell(cmd) => f.write_str(cmd),
+			CommandLine::Exec(args) => {
+				let quoted: Vec<String> = args
+					.iter()
+					.map(|arg| {
+						if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_graphic() && c != '"' && c != '\'') {
+							arg.clone()
+						} else {
+							format!("{:?}", arg)
+						}
+					})
+					.collect();
+				f.write_str(&quoted.join(" "))
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+	Ignore,
+	#[default]
+	Warn,
+	Abort,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Table {
+	command: CommandLine,
+	timeout: Option<f64>,
+	#[serde(default)]
+	on_failure: OnFailure,
+	#[serde(default)]
+	background: bool,
+}
+
+impl<'de> Deserialize<'de> for Hook {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct HookVisitor;
+
+		impl<'de> Visitor<'de> for HookVisitor {
+			type Value = Hook;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a command string, an argument array or a table with `command`")
+			}
+
+			fn visit_str<E: de::Error>(self, command: &str) -> Result<Hook, E> {
+				Ok(Hook::new(CommandLine::Shell(command.to_string())))
+			}
+
+			fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Hook, A::Error> {
+				let args = Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+				if args.is_empty() {
+					return Err(de::Error::invalid_length(0, &"a program and its arguments"));
+				}
+				Ok(Hook::new(CommandLine::Exec(args)))
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Hook, A::Error> {
+				let Table { command, timeout, on_failure, background } =
+					Table::deserialize(de::value::MapAccessDeserializer::new(map))?;
+				if matches!(&command, CommandLine::Exec(args) if args.is_empty()) {
+					return Err(de::Error::invalid_length(0, &"a program and its arguments"));
+				}
+				let timeout = timeout
+					.map(|secs| {
+						Duration::try_from_secs_f64(secs)
+							.ok()
+							.filter(|timeout| secs > 0.0 && !timeout.is_zero())
+							.ok_or_else(|| de::Error::custom(format!("invalid timeout `{:?}`, expected a positive number of seconds", secs)))
+					})
+					.transpose()?;
+				Ok(Hook { command, timeout, on_failure, background })
+			}
+		}
+
+		deserializer.deserialize_any(HookVisitor)
+	}
+}
+
+impl Hook {
+	pub fn new(command: CommandLine) -> Self {
+		Hook { command, timeout: None, on_failure: OnFailure::default(), background: false }
+	}
+}
+
+// Runs `hook`, returning an error only if it fails and its `on_failure` is
+// `abort`. `env` is also the source of placeholders for argument arrays:
+// TINTE_OUTPUT is `{output}`, TINTE_ACCENT is `{accent}` and so on.
+pub fn run(hook: &Hook, env: &[(String, String)]) -> Result<()> {
+	match execute(hook, env, None) {
+		Ok(()) => Ok(()),
+		Err(e) => match hook.on_failure {
+			OnFailure::Ignore => Ok(()),
+			OnFailure::Warn => {
+				eprintln!("Warning: {:#}", e);
+				Ok(())
+			}
+			OnFailure::Abort => Err(e),
+		},
+	}
+}
+
+// Runs an `on_palette` hook with `json` on its stdin. Any failure rejects the
+// palette, whatever the hook's `on_failure`.
+pub fn run_palette(hook: &Hook, env: &[(String, String)], json: &str) -> Result<()> {
+	execute(hook, env, Some(json.as_bytes())).context("Palette rejected by on_palette hook")
+}
+
+fn execute(hook: &Hook, env: &[(String, String)], input: Option<&[u8]>) -> Result<()> {
+	let mut command = match &hook.command {
+		CommandLine::Shell(cmd) => {
+			let mut command = Command::new("sh");
+			command.arg("-c").arg(cmd);
+			command
+		}
+		CommandLine::Exec(args) => {
+			let args: Vec<String> = args.iter().map(|arg| substitute(arg, env)).collect();
+			let mut command = Command::new(&args[0]);
+			command.args(&args[1..]);
+			command
+		}
+	};
+	command.envs(env.iter().map(|(k, v)| (k, v)));
+
+	command.stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() });
+	if hook.background {
+		command.stdout(Stdio::null()).stderr(Stdio::null());
+	}
+
+	let mut child = command.spawn().with_context(|| format!("Failed to run hook: {}", hook.command))?;
+	if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+		// A hook that doesn't read its input closes the pipe early; that's fine.
+		match stdin.write_all(input) {
+			Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+				return Err(e).with_context(|| format!("Failed to write to hook: {}", hook.command));
+			}
+			_ => {}
+		}
+	}
+	if hook.background {
+		return Ok(());
+	}
+
+	let status = match hook.timeout {
+		None => child.wait()?,
+		Some(timeout) => {
+			let deadline = Instant::now().checked_add(timeout);
+			loop {
+				if let Some(status) = child.try_wait()? {
+					break status;
+				}
+				if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+					let _ = child.kill();
+					let _ = child.wait();
+					bail!("Hook timed out after {}s: {}", timeout.as_secs_f64(), hook.command);
+				}
+				thread::sleep(Duration::from_millis(20));
+			}
+		}
+	};
+	check_status(status, &hook.command)
+}
+
+fn check_status(status: ExitStatus, command: &CommandLine) -> Result<()> {
+	if status.success() {
+		return Ok(());
+	}
+	match status.code() {
+		Some(code) => bail!("Hook exited with status {}: {}", code, command),
+		None => bail!("Hook was terminated by a signal: {}", command),
+	}
+}
+
+// Replaces each `{name}` in `arg` whose TINTE_<NAME> is in `env`, leaving
+// other braces alone. `{path}` is the wallpaper, as in `wallpaper_cmd`.
+fn substitute(arg: &str, env: &[(String, String)]) -> String {
+	let lookup = |name: &str| {
+		let name = if name == "path" { "wallpaper" } else { name };
+		env.iter()
+			.find(|(k, _)| k.strip_prefix("TINTE_").is_some_and(|k| k.eq_ignore_ascii_case(name)))
+			.map(|(_, v)| v.as_str())
+	};
+
+	let mut out = String::new();
+	let mut rest = arg;
+	while let Some(start) = rest.find('{') {
+		out.push_str(&rest[..start]);
+		let after = &rest[start + 1..];
+		match after.find('}').and_then(|end| lookup(&after[..end]).map(|value| (end, value))) {
+			Some((end, value)) => {
+				out.push_str(value);
+				rest = &after[end + 1..];
+			}
+			None => {
+				out.push('{');
+				rest = after;
+			}
+		}
+	}
+	out.push_str(rest);
+	out
+}
+
+// Environment passed to hooks: TINTE_MODE, TINTE_SOURCE, TINTE_WALLPAPER
+// (for images), TINTE_OUTPUT (for template hooks), and every palette color
+// as TINTE_<NAME>, e.g. TINTE_ACCENT or TINTE_COLOR4.
+pub fn env(theme: &Theme, output: Option<&Path>) -> Vec<(String, String)> {
+	let palette = &theme.palette;
+	let mut env = vec![
+		("TINTE_MODE".to_string(), theme.mode().to_string()),
+		("TINTE_SOURCE".to_string(), theme.source.to_string()),
+		("TINTE_GENERATOR".to_string(), theme.generator.name().to_string()),
+	];
+	if let Source::Image(path) = &theme.source {
+		env.push(("TINTE_WALLPAPER".to_string(), path.display().to_string()));
+	}
+	if let Some(output) = output {
+		env.push(("TINTE_OUTPUT".to_string(), output.display().to_string()));
+	}
+
+	let mut colors = vec![
+		("background".to_string(), palette.background()),
+		("foreground".to_string(), palette.foreground()),
+	];
+	colors.extend(palette.colors.iter().enumerate().map(|(i, c)| (format!("color{}", i), *c)));
+	colors.extend(palette.semantic().into_iter().map(|(name, c)| (name.to_string(), c)));
+	env.extend(colors.into_iter().map(|(name, c)| (format!("TINTE_{}", name.to_uppercase()), c.to_hex())));
+	env
+}
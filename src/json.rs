@@ -0,0 +1,26 @@
+use crate::color::{Palette, Rgb};
+use crate::template::{Format, Value};
+
+pub fn to_json(palette: &Palette, format: Format) -> String {
+	let fmt = |c: Rgb| format.apply(Value::opaque(c));
+
+	let mut out = String::from("{\n");
+	for (i, color) in palette.colors.iter().enumerate() {
+		let value = fmt(*color);
+		match i {
+			0 => out.push_str(&format!("  \"background\": \"{}\",\n", value)),
+			15 => out.push_str(&format!("  \"foreground\": \"{}\",\n", value)),
+			_ => out.push_str(&format!("  \"color{}\": \"{}\",\n", i, value)),
+		}
+	}
+	for (i, color) in palette.extended().iter().enumerate() {
+		out.push_str(&format!("  \"color{}\": \"{}\",\n", i + 16, fmt(*color)));
+	}
+	let semantic = palette.semantic();
+	for (i, (name, color)) in semantic.iter().enumerate() {
+		let sep = if i + 1 < semantic.len() { "," } else { "" };
+		out.push_str(&format!("  \"{}\": \"{}\"{}\n", name, fmt(*color), sep));
+	}
+	out.push_str("}\n");
+	out
+}
@@ -1,4 +1,12 @@
+pub mod base16;
 pub mod color;
 pub mod config;
 pub mod extraction;
+pub mod hook;
+pub mod json;
+pub mod output;
+pub mod preset;
+pub mod state;
 pub mod template;
+pub mod terminal;
+pub mod theme;
@@ -1,12 +1,17 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
-use std::process::Command;
 
+use tinte::base16;
 use tinte::color::{Palette, Rgb};
 use tinte::config::{expand_path, Config};
-use tinte::extraction::extract_palette;
-use tinte::template::process_templates;
+use tinte::hook::{self, CommandLine};
+use tinte::json;
+use tinte::preset;
+use tinte::state;
+use tinte::template::{builtin, process_templates, validate_templates, Format, Options};
+use tinte::terminal;
+use tinte::theme::{Source, Theme};
 
 #[derive(Parser)]
 #[command(name = "tinte")]
@@ -31,11 +36,23 @@ struct Cli {
 	#[arg(long, global = true)]
 	dry_run: bool,
 
+	#[arg(long, global = true)]
+	force: bool,
+
+	#[arg(long, global = true)]
+	diff: bool,
+
 	#[arg(long, global = true)]
 	show_colors: bool,
 
+	#[arg(long, global = true)]
+	apply_terminals: bool,
+
 	#[arg(short, long, global = true)]
 	json: Option<JsonFormat>,
+
+	#[arg(long, global = true)]
+	scheme: Option<SchemeFormat>,
 }
 
 #[derive(Clone, Copy, clap::ValueEnum)]
@@ -49,12 +66,63 @@ enum JsonFormat {
 	Hex,
 	Rgb,
 	Strip,
+	Rgba,
+	Hsl,
+	Oklch,
+	#[value(name = "0x")]
+	Hex0x,
+	Float,
+	Int,
+}
+
+impl JsonFormat {
+	fn format(self) -> Format {
+		match self {
+			JsonFormat::Hex => Format::Hex,
+			JsonFormat::Rgb => Format::Rgb,
+			JsonFormat::Strip => Format::Strip,
+			JsonFormat::Rgba => Format::Rgba(None),
+			JsonFormat::Hsl => Format::Hsl,
+			JsonFormat::Oklch => Format::Oklch,
+			JsonFormat::Hex0x => Format::Hex0x,
+			JsonFormat::Float => Format::Float,
+			JsonFormat::Int => Format::Int,
+		}
+	}
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SchemeFormat {
+	Base16,
+	Base24,
 }
 
 #[derive(Subcommand)]
 enum Commands {
 	Image { path: PathBuf },
 	Color { hex: String },
+	Preset {
+		#[arg(required_unless_present = "list")]
+		name: Option<String>,
+		#[arg(long, conflicts_with = "name")]
+		list: bool,
+	},
+	// Reapplies the last theme without extracting the palette again.
+	Restore,
+	Templates {
+		#[command(subcommand)]
+		command: TemplatesCommand,
+	},
+}
+
+#[derive(Subcommand)]
+enum TemplatesCommand {
+	// Lists the built-in templates.
+	List,
+	// Prints a built-in template, e.g. to copy it into the templates directory.
+	Export { name: String },
+	// Checks the configured templates without writing anything.
+	Validate,
 }
 
 fn main() -> Result<()> {
@@ -63,7 +131,7 @@ fn main() -> Result<()> {
 
 	let config = Config::load(cli.config.as_ref())?;
 
-	match cli.command {
+	match &cli.command {
 		Commands::Image { path } => {
 			let path_str = path.to_str()
 				.ok_or_else(|| anyhow::anyhow!("Invalid path: {:?}", path))?;
@@ -73,51 +141,137 @@ fn main() -> Result<()> {
 				println!("Extracting colors from: {}", path.display());
 			}
 
-			let palette = extract_palette(&path, light_mode)?;
+			let theme = Theme::generate(Source::Image(path), light_mode)?;
+			output_theme(&cli, &config, &theme)?;
+			set_wallpaper(&cli, &config, &theme)?;
+			finish(&cli, &config, &theme)?;
+		}
 
-			if cli.show_colors {
-				print_palette(&palette);
-			}
+		Commands::Color { hex } => {
+			let source = Rgb::from_hex(hex)
+				.ok_or_else(|| anyhow::anyhow!("Invalid hex color: {}", hex))?;
 
-			if let Some(format) = cli.json {
-				print_json(&palette, format);
+			if !cli.quiet {
+				println!("Generating palette from: {}", source.to_hex());
 			}
 
-			if !config.templates.is_empty() {
-				process_templates(&config, &palette, cli.dry_run)?;
-			}
+			let theme = Theme::generate(Source::Color(source), light_mode)?;
+			output_theme(&cli, &config, &theme)?;
+			finish(&cli, &config, &theme)?;
+		}
 
-			if !cli.dry_run && let Some(ref cmd) = config.config.wallpaper_cmd {
-				let expanded = path.to_str()
-					.ok_or_else(|| anyhow::anyhow!("Invalid expanded path: {:?}", path))?;
-				let cmd = cmd.replace("{path}", expanded);
-				if cli.verbose {
-					println!("Setting wallpaper: {}", cmd);
+		Commands::Preset { name, list } => {
+			if *list {
+				for p in preset::PRESETS {
+					println!("{:22} {}", p.name, p.variants().join(", "));
 				}
-				Command::new("sh").arg("-c").arg(&cmd).status()?;
+				return Ok(());
 			}
-		}
 
-		Commands::Color { hex } => {
-			let source = Rgb::from_hex(&hex)
-				.ok_or_else(|| anyhow::anyhow!("Invalid hex color: {}", hex))?;
+			let name = name.as_deref().unwrap_or_default();
+			let preset = preset::find(name)
+				.ok_or_else(|| anyhow::anyhow!("Unknown preset: {} (see `tinte preset --list`)", name))?;
 
 			if !cli.quiet {
-				println!("Generating palette from: {}", source.to_hex());
+				println!("Using preset: {}", preset.name);
 			}
 
-			let palette = generate_from_color(source, light_mode);
+			let theme = Theme::generate(Source::Preset(preset.name.to_string()), light_mode)?;
+			if theme.light_mode != light_mode && !cli.quiet {
+				let (wanted, used) = if light_mode { ("light", "dark") } else { ("dark", "light") };
+				println!("Preset {} has no {} variant, using {}", preset.name, wanted, used);
+			}
+			output_theme(&cli, &config, &theme)?;
+			finish(&cli, &config, &theme)?;
+		}
 
-			if cli.show_colors {
-				print_palette(&palette);
+		Commands::Restore => {
+			let theme = state::load()?;
+			if !cli.quiet {
+				println!("Restoring {} theme from: {}", theme.mode(), theme.source);
 			}
+			output_theme(&cli, &config, &theme)?;
+			set_wallpaper(&cli, &config, &theme)?;
+			finish(&cli, &config, &theme)?;
+		}
 
-			if let Some(format) = cli.json {
-				print_json(&palette, format);
+		Commands::Templates { command } => match command {
+			TemplatesCommand::List => {
+				for (name, file) in builtin::list() {
+					println!("{:24} {}", name, file);
+				}
+			}
+			TemplatesCommand::Export { name } => {
+				let (_, source) = builtin::find(name)
+					.ok_or_else(|| anyhow::anyhow!("Unknown built-in template: {} (see `tinte templates list`)", name))?;
+				print!("{}", source);
 			}
+			TemplatesCommand::Validate => {
+				let problems = validate_templates(&config);
+				for (template, problem) in &problems {
+					println!("{}: {}", template, problem);
+				}
+				if !problems.is_empty() {
+					anyhow::bail!("{} problem{} found", problems.len(), if problems.len() == 1 { "" } else { "s" });
+				}
+				if !cli.quiet {
+					println!("{} template{} OK", config.templates.len(), if config.templates.len() == 1 { "" } else { "s" });
+				}
+			}
+		},
+	}
+
+	Ok(())
+}
 
-			if !config.templates.is_empty() {
-				process_templates(&config, &palette, cli.dry_run)?;
+fn output_theme(cli: &Cli, config: &Config, theme: &Theme) -> Result<()> {
+	let palette = &theme.palette;
+
+	if cli.show_colors {
+		print_palette(palette);
+	}
+
+	if let Some(format) = cli.json {
+		print!("{}", json::to_json(palette, format.format()));
+	}
+
+	if let Some(format) = cli.scheme {
+		let system = match format {
+			SchemeFormat::Base16 => base16::System::Base16,
+			SchemeFormat::Base24 => base16::System::Base24,
+		};
+		print!("{}", base16::to_yaml(palette, system));
+	}
+
+	if !cli.dry_run && let Some(ref cmd) = config.config.on_palette {
+		if !cli.quiet {
+			println!("Running hook: {}", cmd.command);
+		}
+		hook::run_palette(cmd, &hook::env(theme, None), &json::to_json(palette, Format::Hex))?;
+	}
+
+	if !config.templates.is_empty() {
+		let options = Options { dry_run: cli.dry_run, force: cli.force, diff: cli.diff, quiet: cli.quiet };
+		process_templates(config, theme, options)?;
+	}
+
+	if cli.apply_terminals {
+		if cli.dry_run {
+			if !cli.quiet {
+				println!("[dry-run] Would apply colors to running terminals");
+			}
+		} else {
+			let sequences = terminal::sequences(palette);
+			let saved = terminal::save(&sequences)?;
+			let ttys = terminal::apply(&sequences)?;
+			if cli.verbose {
+				for tty in &ttys {
+					println!("Applied colors: {}", tty.display());
+				}
+				println!("Wrote: {}", saved.display());
+			}
+			if !cli.quiet {
+				println!("Applied colors to {} terminal{}", ttys.len(), if ttys.len() == 1 { "" } else { "s" });
 			}
 		}
 	}
@@ -125,6 +279,43 @@ fn main() -> Result<()> {
 	Ok(())
 }
 
+fn set_wallpaper(cli: &Cli, config: &Config, theme: &Theme) -> Result<()> {
+	let (Source::Image(path), Some(cmd)) = (&theme.source, &config.config.wallpaper_cmd) else {
+		return Ok(());
+	};
+	if cli.dry_run {
+		return Ok(());
+	}
+
+	let mut cmd = cmd.clone();
+	// The string form goes to the shell as written; argument
+	// arrays substitute {path} safely in hook::run.
+	if let CommandLine::Shell(ref mut shell) = cmd.command {
+		let expanded = path.to_str()
+			.ok_or_else(|| anyhow::anyhow!("Invalid expanded path: {:?}", path))?;
+		*shell = shell.replace("{path}", expanded);
+	}
+	if cli.verbose {
+		println!("Setting wallpaper: {}", cmd.command);
+	}
+	hook::run(&cmd, &hook::env(theme, None))
+}
+
+// Remembers the applied theme for `tinte restore` and runs `on_complete`.
+fn finish(cli: &Cli, config: &Config, theme: &Theme) -> Result<()> {
+	if cli.dry_run {
+		return Ok(());
+	}
+	state::save(theme)?;
+	if let Some(ref cmd) = config.config.on_complete {
+		if !cli.quiet {
+			println!("Running hook: {}", cmd.command);
+		}
+		hook::run(cmd, &hook::env(theme, None))?;
+	}
+	Ok(())
+}
+
 fn print_palette(palette: &Palette) {
 	println!("\nPalette:");
 	for (i, color) in palette.colors.iter().enumerate() {
@@ -153,88 +344,23 @@ fn print_palette(palette: &Palette) {
 		);
 	}
 
+	println!("\nExtended:");
+	let extended = palette.extended();
+	for row in extended.chunks(36) {
+		print!("  ");
+		for color in row {
+			print!("\x1b[48;2;{};{};{}m  ", color.r, color.g, color.b);
+		}
+		println!("\x1b[0m");
+	}
+
 	println!("\nSemantic:");
-	let sem = [
-		("accent      ", palette.accent),
-		("accent_dim  ", palette.accent_dim),
-		("accent_brigh", palette.accent_bright),
-		("secondary   ", palette.secondary),
-		("surface     ", palette.surface),
-		("on_accent   ", palette.on_accent),
-		("on_surface  ", palette.on_surface),
-	];
-	for (label, color) in sem {
+	for (label, color) in palette.semantic() {
 		println!(
-			"     {} {} \x1b[48;2;{};{};{}m    \x1b[0m",
+			"     {:13} {} \x1b[48;2;{};{};{}m    \x1b[0m",
 			label, color.to_hex(), color.r, color.g, color.b
 		);
 	}
 	println!();
 }
 
-fn print_json(palette: &Palette, format: JsonFormat) {
-	let fmt = |c: Rgb| match format {
-		JsonFormat::Hex => c.to_hex(),
-		JsonFormat::Strip => c.to_hex_strip(),
-		JsonFormat::Rgb => c.to_rgb_string(),
-	};
-
-	println!("{{");
-	for (i, color) in palette.colors.iter().enumerate() {
-		let value = fmt(*color);
-		match i {
-			0 => println!("  \"background\": \"{}\",", value),
-			15 => println!("  \"foreground\": \"{}\",", value),
-			_ => println!("  \"color{}\": \"{}\",", i, value),
-		}
-	}
-	println!("  \"accent\": \"{}\",", fmt(palette.accent));
-	println!("  \"accent_dim\": \"{}\",", fmt(palette.accent_dim));
-	println!("  \"accent_bright\": \"{}\",", fmt(palette.accent_bright));
-	println!("  \"secondary\": \"{}\",", fmt(palette.secondary));
-	println!("  \"surface\": \"{}\",", fmt(palette.surface));
-	println!("  \"on_accent\": \"{}\",", fmt(palette.on_accent));
-	println!("  \"on_surface\": \"{}\"", fmt(palette.on_surface));
-	println!("}}");
-}
-
-fn generate_from_color(source: Rgb, light_mode: bool) -> Palette {
-	use tinte::color::Hsl;
-
-	let hsl = source.to_hsl();
-	let bg_l = if light_mode { 0.92 } else { 0.08 };
-	let fg_l = if light_mode { 0.15 } else { 0.85 };
-	let color_l = if light_mode { 0.45 } else { 0.55 };
-	let bright_l = if light_mode { 0.35 } else { 0.65 };
-
-	let background = Hsl::new(hsl.h, hsl.s.min(0.15), bg_l).to_rgb();
-	let foreground = Hsl::new(hsl.h, hsl.s.min(0.1), fg_l).to_rgb();
-	let bright_black = Hsl::new(hsl.h, hsl.s.min(0.15), if light_mode { 0.75 } else { 0.25 }).to_rgb();
-
-	let hue_offsets = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
-	let colors: Vec<Rgb> = hue_offsets
-		.iter()
-		.map(|offset| Hsl::new((hsl.h + offset) % 360.0, 0.6, color_l).to_rgb())
-		.collect();
-	let bright: Vec<Rgb> = hue_offsets
-		.iter()
-		.map(|offset| Hsl::new((hsl.h + offset) % 360.0, 0.7, bright_l).to_rgb())
-		.collect();
-
-	let accent = Hsl::new(hsl.h, hsl.s.max(0.5), color_l).to_rgb();
-	let accent_dim = Hsl::new(hsl.h, hsl.s.max(0.4), if light_mode { 0.6 } else { 0.35 }).to_rgb();
-	let accent_bright = Hsl::new(hsl.h, (hsl.s * 1.1).min(1.0), if light_mode { 0.35 } else { 0.7 }).to_rgb();
-	let secondary = Hsl::new((hsl.h + 180.0) % 360.0, 0.4, color_l).to_rgb();
-	let surface = Hsl::new(hsl.h, 0.1, if light_mode { 0.87 } else { 0.14 }).to_rgb();
-	let on_accent = if color_l > 0.5 {
-		Hsl::new(hsl.h, 0.15, 0.1).to_rgb()
-	} else {
-		Hsl::new(hsl.h, 0.05, 0.95).to_rgb()
-	};
-
-	Palette::new(
-		[background, colors[0], colors[1], colors[2], colors[3], colors[4], colors[5], foreground,
-		 bright_black, bright[0], bright[1], bright[2], bright[3], bright[4], bright[5], foreground],
-		accent, accent_dim, accent_bright, secondary, surface, on_accent, foreground,
-	)
-}
@@ -0,0 +1,135 @@
+use crate::state;
+use anyhow::{Context, Result};
+use similar::{ChangeTag, TextDiff};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+// Writes template outputs, possibly from several threads, remembering what
+// each file held before so that a failed run can be undone.
+pub struct Writer {
+	backup: bool,
+	previous: Mutex<Vec<(PathBuf, Option<Vec<u8>>)>>,
+}
+
+impl Writer {
+	pub fn new(backup: bool) -> Self {
+		Self { backup, previous: Mutex::new(Vec::new()) }
+	}
+
+	pub fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+		// Replace the target of a symlinked output (e.g. into a dotfiles
+		// repo), not the link itself.
+		let path = match fs::canonicalize(path) {
+			Ok(target) if path.is_symlink() => target,
+			_ => path.to_path_buf(),
+		};
+
+		let previous = match fs::read(&path) {
+			Ok(data) => Some(data),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+			Err(e) => return Err(e).with_context(|| format!("Failed to read: {}", path.display())),
+		};
+
+		if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			create_dir(parent)?;
+		}
+		if self.backup && previous.is_some() {
+			let backup = backup_path(&path)?;
+			if let Some(parent) = backup.parent() {
+				create_dir(parent)?;
+			}
+			fs::copy(&path, &backup).with_context(|| format!("Failed to write backup: {}", backup.display()))?;
+		}
+		write_atomic(&path, content)?;
+
+		self.previous.lock().unwrap_or_else(|e| e.into_inner()).push((path, previous));
+		Ok(())
+	}
+
+	// Restores every file written so far to its previous content, removing
+	// files that did not exist before.
+	pub fn rollback(&self) {
+		let previous = std::mem::take(&mut *self.previous.lock().unwrap_or_else(|e| e.into_inner()));
+		for (path, previous) in previous.into_iter().rev() {
+			let result = match previous {
+				Some(data) => write_atomic(&path, &data),
+				None => fs::remove_file(&path).with_context(|| format!("Failed to remove: {}", path.display())),
+			};
+			match result {
+				Ok(()) => eprintln!("Rolled back: {}", path.display()),
+				Err(e) => eprintln!("Rollback failed: {:#}", e),
+			}
+		}
+	}
+}
+
+// Whether `path` already holds exactly `content`.
+pub fn is_current(path: &Path, content: &[u8]) -> bool {
+	fs::read(path).is_ok_and(|existing| existing == content)
+}
+
+// Unified diff from the current content of `path` to `content`, with ANSI
+// colors if `color` is set.
+pub fn diff(path: &Path, content: &str, color: bool) -> String {
+	let old = fs::read_to_string(path).unwrap_or_default();
+	let new_name = path.display().to_string();
+	let old_name = if path.exists() { new_name.clone() } else { "/dev/null".to_string() };
+	let paint = |code: &str, text: String| if color { format!("\x1b[{}m{}\x1b[0m\n", code, text) } else { text + "\n" };
+
+	let mut out = paint("1", format!("--- {}\n+++ {}", old_name, new_name));
+	let diff = TextDiff::from_lines(old.as_str(), content);
+	for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+		out.push_str(&paint("36", hunk.header().to_string()));
+		for change in hunk.iter_changes() {
+			let line = change.value().trim_end_matches('\n');
+			match change.tag() {
+				ChangeTag::Delete => out.push_str(&paint("31", format!("-{}", line))),
+				ChangeTag::Insert => out.push_str(&paint("32", format!("+{}", line))),
+				ChangeTag::Equal => out.push_str(&format!(" {}\n", line)),
+			}
+		}
+	}
+	out
+}
+
+// Backups live outside the output's directory, which apps may scan (e.g.
+// Ghostty lists every file in its themes directory), mirroring the output's
+// absolute path under `$XDG_STATE_HOME/tinte/backups`.
+pub fn backup_path(path: &Path) -> Result<PathBuf> {
+	let path = std::path::absolute(path).with_context(|| format!("Invalid output path: {}", path.display()))?;
+	let relative: PathBuf = path
+		.components()
+		.filter(|c| matches!(c, Component::Normal(_)))
+		.collect();
+	Ok(state::dir().join("backups").join(relative))
+}
+
+fn create_dir(dir: &Path) -> Result<()> {
+	fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))
+}
+
+// Writes `content` to a temporary file next to `path` and renames it into
+// place, so readers never see a partially written file.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+	let name = path
+		.file_name()
+		.with_context(|| format!("Invalid output path: {}", path.display()))?;
+	let tmp = path.with_file_name(format!(".{}.tinte-{}", name.to_string_lossy(), std::process::id()));
+
+	let result = (|| {
+		let mut file = File::create(&tmp)?;
+		file.write_all(content)?;
+		if let Ok(meta) = fs::metadata(path) {
+			file.set_permissions(meta.permissions())?;
+		}
+		file.sync_all()?;
+		fs::rename(&tmp, path)
+	})();
+
+	if result.is_err() {
+		let _ = fs::remove_file(&tmp);
+	}
+	result.with_context(|| format!("Failed to write: {}", path.display()))
+}
@@ -0,0 +1,297 @@
+use crate::color::{Palette, Rgb};
+
+struct Variant {
+	colors: [&'static str; 16],
+	accent: &'static str,
+	accent_dim: &'static str,
+	accent_bright: &'static str,
+	secondary: &'static str,
+	surface: &'static str,
+	on_accent: &'static str,
+	on_surface: &'static str,
+}
+
+impl Variant {
+	fn to_palette(&self) -> Palette {
+		let hex = |s: &str| Rgb::from_hex(s).unwrap();
+		Palette::new(
+			self.colors.map(hex),
+			hex(self.accent),
+			hex(self.accent_dim),
+			hex(self.accent_bright),
+			hex(self.secondary),
+			hex(self.surface),
+			hex(self.on_accent),
+			hex(self.on_surface),
+		)
+	}
+}
+
+pub struct Preset {
+	pub name: &'static str,
+	dark: Option<&'static Variant>,
+	light: Option<&'static Variant>,
+}
+
+impl Preset {
+	pub fn palette(&self, light_mode: bool) -> Option<Palette> {
+		if light_mode { self.light } else { self.dark }.map(Variant::to_palette)
+	}
+
+	pub fn palette_or_fallback(&self, light_mode: bool) -> Palette {
+		self.palette(light_mode)
+			.or_else(|| self.palette(!light_mode))
+			.expect("preset without variants")
+	}
+
+	pub fn variants(&self) -> Vec<&'static str> {
+		let mut v = Vec::new();
+		if self.dark.is_some() {
+			v.push("dark");
+		}
+		if self.light.is_some() {
+			v.push("light");
+		}
+		v
+	}
+}
+
+pub fn find(name: &str) -> Option<&'static Preset> {
+	let name: String = name
+		.trim()
+		.to_lowercase()
+		.chars()
+		.map(|c| match c {
+			'_' | ' ' => '-',
+			'é' => 'e',
+			c => c,
+		})
+		.collect();
+	PRESETS.iter().find(|p| p.name == name)
+}
+
+pub const PRESETS: &[Preset] = &[
+	Preset { name: "tokyo-night", dark: Some(&TOKYO_NIGHT), light: Some(&TOKYO_NIGHT_DAY) },
+	Preset { name: "gruvbox", dark: Some(&GRUVBOX_DARK), light: Some(&GRUVBOX_LIGHT) },
+	Preset { name: "catppuccin", dark: Some(&CATPPUCCIN_MOCHA), light: Some(&CATPPUCCIN_LATTE) },
+	Preset { name: "catppuccin-mocha", dark: Some(&CATPPUCCIN_MOCHA), light: None },
+	Preset { name: "catppuccin-macchiato", dark: Some(&CATPPUCCIN_MACCHIATO), light: None },
+	Preset { name: "catppuccin-frappe", dark: Some(&CATPPUCCIN_FRAPPE), light: None },
+	Preset { name: "catppuccin-latte", dark: None, light: Some(&CATPPUCCIN_LATTE) },
+	Preset { name: "nord", dark: Some(&NORD), light: None },
+	Preset { name: "dracula", dark: Some(&DRACULA), light: None },
+	Preset { name: "solarized", dark: Some(&SOLARIZED_DARK), light: Some(&SOLARIZED_LIGHT) },
+	Preset { name: "rose-pine", dark: Some(&ROSE_PINE), light: Some(&ROSE_PINE_DAWN) },
+	Preset { name: "rose-pine-moon", dark: Some(&ROSE_PINE_MOON), light: None },
+];
+
+// Same values as `Palette::default`.
+const TOKYO_NIGHT: Variant = Variant {
+	colors: [
+		"#1a1b26", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#a9b1d6",
+		"#414868", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#c0caf5",
+	],
+	accent: "#7aa2f7",
+	accent_dim: "#3d5179",
+	accent_bright: "#a9c4fa",
+	secondary: "#bb9af7",
+	surface: "#24283b",
+	on_accent: "#1a1b26",
+	on_surface: "#c0caf5",
+};
+
+const TOKYO_NIGHT_DAY: Variant = Variant {
+	colors: [
+		"#e1e2e7", "#f52a65", "#587539", "#8c6c3e", "#2e7de9", "#9854f1", "#007197", "#6172b0",
+		"#a1a6c5", "#f52a65", "#587539", "#8c6c3e", "#2e7de9", "#9854f1", "#007197", "#3760bf",
+	],
+	accent: "#2e7de9",
+	accent_dim: "#92a6d5",
+	accent_bright: "#1f5fbf",
+	secondary: "#9854f1",
+	surface: "#d0d5e3",
+	on_accent: "#e1e2e7",
+	on_surface: "#3760bf",
+};
+
+const GRUVBOX_DARK: Variant = Variant {
+	colors: [
+		"#282828", "#cc241d", "#98971a", "#d79921", "#458588", "#b16286", "#689d6a", "#a89984",
+		"#928374", "#fb4934", "#b8bb26", "#fabd2f", "#83a598", "#d3869b", "#8ec07c", "#ebdbb2",
+	],
+	accent: "#d79921",
+	accent_dim: "#b57614",
+	accent_bright: "#fabd2f",
+	secondary: "#83a598",
+	surface: "#3c3836",
+	on_accent: "#282828",
+	on_surface: "#ebdbb2",
+};
+
+const GRUVBOX_LIGHT: Variant = Variant {
+	colors: [
+		"#fbf1c7", "#cc241d", "#98971a", "#d79921", "#458588", "#b16286", "#689d6a", "#7c6f64",
+		"#928374", "#9d0006", "#79740e", "#b57614", "#076678", "#8f3f71", "#427b58", "#3c3836",
+	],
+	accent: "#b57614",
+	accent_dim: "#d79921",
+	accent_bright: "#8a5a0f",
+	secondary: "#076678",
+	surface: "#ebdbb2",
+	on_accent: "#fbf1c7",
+	on_surface: "#3c3836",
+};
+
+const CATPPUCCIN_MOCHA: Variant = Variant {
+	colors: [
+		"#1e1e2e", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#bac2de",
+		"#585b70", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#cdd6f4",
+	],
+	accent: "#cba6f7",
+	accent_dim: "#8b6bbf",
+	accent_bright: "#dcc4fa",
+	secondary: "#89b4fa",
+	surface: "#313244",
+	on_accent: "#1e1e2e",
+	on_surface: "#cdd6f4",
+};
+
+const CATPPUCCIN_MACCHIATO: Variant = Variant {
+	colors: [
+		"#24273a", "#ed8796", "#a6da95", "#eed49f", "#8aadf4", "#f5bde6", "#8bd5ca", "#b8c0e0",
+		"#5b6078", "#ed8796", "#a6da95", "#eed49f", "#8aadf4", "#f5bde6", "#8bd5ca", "#cad3f5",
+	],
+	accent: "#c6a0f6",
+	accent_dim: "#8a6cb8",
+	accent_bright: "#d9bff9",
+	secondary: "#8aadf4",
+	surface: "#363a4f",
+	on_accent: "#24273a",
+	on_surface: "#cad3f5",
+};
+
+const CATPPUCCIN_FRAPPE: Variant = Variant {
+	colors: [
+		"#303446", "#e78284", "#a6d189", "#e5c890", "#8caaee", "#f4b8e4", "#81c8be", "#b5bfe2",
+		"#626880", "#e78284", "#a6d189", "#e5c890", "#8caaee", "#f4b8e4", "#81c8be", "#c6d0f5",
+	],
+	accent: "#ca9ee6",
+	accent_dim: "#8e6ca8",
+	accent_bright: "#dcbdef",
+	secondary: "#8caaee",
+	surface: "#414559",
+	on_accent: "#303446",
+	on_surface: "#c6d0f5",
+};
+
+const CATPPUCCIN_LATTE: Variant = Variant {
+	colors: [
+		"#eff1f5", "#d20f39", "#40a02b", "#df8e1d", "#1e66f5", "#ea76cb", "#179299", "#5c5f77",
+		"#acb0be", "#d20f39", "#40a02b", "#df8e1d", "#1e66f5", "#ea76cb", "#179299", "#4c4f69",
+	],
+	accent: "#8839ef",
+	accent_dim: "#b48ef5",
+	accent_bright: "#6a1fd0",
+	secondary: "#1e66f5",
+	surface: "#ccd0da",
+	on_accent: "#eff1f5",
+	on_surface: "#4c4f69",
+};
+
+const NORD: Variant = Variant {
+	colors: [
+		"#2e3440", "#bf616a", "#a3be8c", "#ebcb8b", "#81a1c1", "#b48ead", "#88c0d0", "#e5e9f0",
+		"#4c566a", "#bf616a", "#a3be8c", "#ebcb8b", "#81a1c1", "#b48ead", "#8fbcbb", "#eceff4",
+	],
+	accent: "#88c0d0",
+	accent_dim: "#5e81ac",
+	accent_bright: "#b0d5df",
+	secondary: "#b48ead",
+	surface: "#3b4252",
+	on_accent: "#2e3440",
+	on_surface: "#d8dee9",
+};
+
+const DRACULA: Variant = Variant {
+	colors: [
+		"#282a36", "#ff5555", "#50fa7b", "#f1fa8c", "#bd93f9", "#ff79c6", "#8be9fd", "#bfbfbf",
+		"#6272a4", "#ff6e6e", "#69ff94", "#ffffa5", "#d6acff", "#ff92df", "#a4ffff", "#f8f8f2",
+	],
+	accent: "#bd93f9",
+	accent_dim: "#8a63d2",
+	accent_bright: "#d6acff",
+	secondary: "#ff79c6",
+	surface: "#44475a",
+	on_accent: "#282a36",
+	on_surface: "#f8f8f2",
+};
+
+const SOLARIZED_DARK: Variant = Variant {
+	colors: [
+		"#002b36", "#dc322f", "#859900", "#b58900", "#268bd2", "#d33682", "#2aa198", "#839496",
+		"#586e75", "#cb4b16", "#859900", "#b58900", "#268bd2", "#6c71c4", "#2aa198", "#93a1a1",
+	],
+	accent: "#268bd2",
+	accent_dim: "#1a6091",
+	accent_bright: "#5fa8e0",
+	secondary: "#2aa198",
+	surface: "#073642",
+	on_accent: "#fdf6e3",
+	on_surface: "#839496",
+};
+
+const SOLARIZED_LIGHT: Variant = Variant {
+	colors: [
+		"#fdf6e3", "#dc322f", "#859900", "#b58900", "#268bd2", "#d33682", "#2aa198", "#657b83",
+		"#93a1a1", "#cb4b16", "#859900", "#b58900", "#268bd2", "#6c71c4", "#2aa198", "#586e75",
+	],
+	accent: "#268bd2",
+	accent_dim: "#7fb5e3",
+	accent_bright: "#1a6091",
+	secondary: "#2aa198",
+	surface: "#eee8d5",
+	on_accent: "#fdf6e3",
+	on_surface: "#657b83",
+};
+
+const ROSE_PINE: Variant = Variant {
+	colors: [
+		"#191724", "#eb6f92", "#31748f", "#f6c177", "#9ccfd8", "#c4a7e7", "#ebbcba", "#e0def4",
+		"#6e6a86", "#eb6f92", "#31748f", "#f6c177", "#9ccfd8", "#c4a7e7", "#ebbcba", "#e0def4",
+	],
+	accent: "#c4a7e7",
+	accent_dim: "#907aa9",
+	accent_bright: "#ddc7f5",
+	secondary: "#ebbcba",
+	surface: "#1f1d2e",
+	on_accent: "#191724",
+	on_surface: "#e0def4",
+};
+
+const ROSE_PINE_MOON: Variant = Variant {
+	colors: [
+		"#232136", "#eb6f92", "#3e8fb0", "#f6c177", "#9ccfd8", "#c4a7e7", "#ea9a97", "#e0def4",
+		"#6e6a86", "#eb6f92", "#3e8fb0", "#f6c177", "#9ccfd8", "#c4a7e7", "#ea9a97", "#e0def4",
+	],
+	accent: "#c4a7e7",
+	accent_dim: "#907aa9",
+	accent_bright: "#ddc7f5",
+	secondary: "#ea9a97",
+	surface: "#2a273f",
+	on_accent: "#232136",
+	on_surface: "#e0def4",
+};
+
+const ROSE_PINE_DAWN: Variant = Variant {
+	colors: [
+		"#faf4ed", "#b4637a", "#286983", "#ea9d34", "#56949f", "#907aa9", "#d7827e", "#575279",
+		"#9893a5", "#b4637a", "#286983", "#ea9d34", "#56949f", "#907aa9", "#d7827e", "#575279",
+	],
+	accent: "#907aa9",
+	accent_dim: "#b9a6cf",
+	accent_bright: "#6f5a8c",
+	secondary: "#d7827e",
+	surface: "#f2e9e1",
+	on_accent: "#faf4ed",
+	on_surface: "#575279",
+};
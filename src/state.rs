@@ -0,0 +1,134 @@
+use crate::color::{Palette, Rgb};
+use crate::config::expand_path;
+use crate::output;
+use crate::theme::{Generator, Source, Theme};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// The last applied theme, kept in `$XDG_STATE_HOME/tinte/state.toml` so
+// `tinte restore` can reapply it without extracting the palette again.
+#[derive(Serialize, Deserialize)]
+struct State {
+	mode: String,
+	generator: String,
+	// The image is also the wallpaper.
+	source: SavedSource,
+	palette: SavedPalette,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SavedSource {
+	Image(PathBuf),
+	Color(String),
+	Preset(String),
+}
+
+// The inputs of `Palette::new`; the remaining colors are derived from them.
+#[derive(Serialize, Deserialize)]
+struct SavedPalette {
+	colors: Vec<String>,
+	accent: String,
+	accent_dim: String,
+	accent_bright: String,
+	secondary: String,
+	surface: String,
+	on_accent: String,
+	on_surface: String,
+}
+
+const GENERATORS: [Generator; 5] =
+	[Generator::Chromatic, Generator::Monochrome, Generator::Subtle, Generator::Seed, Generator::Preset];
+
+// `$XDG_STATE_HOME/tinte`, which also holds output backups.
+pub fn dir() -> PathBuf {
+	dirs::state_dir()
+		.unwrap_or_else(|| expand_path("~/.local/state"))
+		.join("tinte")
+}
+
+pub fn path() -> PathBuf {
+	dir().join("state.toml")
+}
+
+pub fn save(theme: &Theme) -> Result<()> {
+	let p = &theme.palette;
+	let state = State {
+		mode: theme.mode().to_string(),
+		generator: theme.generator.name().to_string(),
+		source: match &theme.source {
+			Source::Image(path) => SavedSource::Image(path.clone()),
+			Source::Color(color) => SavedSource::Color(color.to_hex()),
+			Source::Preset(name) => SavedSource::Preset(name.clone()),
+		},
+		palette: SavedPalette {
+			colors: p.colors.iter().map(|c| c.to_hex()).collect(),
+			accent: p.accent.to_hex(),
+			accent_dim: p.accent_dim.to_hex(),
+			accent_bright: p.accent_bright.to_hex(),
+			secondary: p.secondary.to_hex(),
+			surface: p.surface.to_hex(),
+			on_accent: p.on_accent.to_hex(),
+			on_surface: p.on_surface.to_hex(),
+		},
+	};
+
+	let path = path();
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+	}
+	let content = toml::to_string(&state).context("Failed to serialize state")?;
+	output::write_atomic(&path, content.as_bytes())
+}
+
+pub fn load() -> Result<Theme> {
+	let path = path();
+	let content = match fs::read_to_string(&path) {
+		Ok(content) => content,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+			return Err(anyhow!("No saved theme to restore: {} does not exist", path.display()));
+		}
+		Err(e) => return Err(e).with_context(|| format!("Failed to read state: {}", path.display())),
+	};
+	let state: State = toml::from_str(&content).with_context(|| format!("Failed to parse state: {}", path.display()))?;
+	to_theme(state).with_context(|| format!("Invalid state: {}", path.display()))
+}
+
+fn to_theme(state: State) -> Result<Theme> {
+	let rgb = |hex: &str| Rgb::from_hex(hex).ok_or_else(|| anyhow!("invalid color: {}", hex));
+
+	let light_mode = match state.mode.as_str() {
+		"dark" => false,
+		"light" => true,
+		mode => return Err(anyhow!("invalid mode: {}", mode)),
+	};
+	let generator = GENERATORS
+		.into_iter()
+		.find(|g| g.name() == state.generator)
+		.ok_or_else(|| anyhow!("invalid generator: {}", state.generator))?;
+	let source = match state.source {
+		SavedSource::Image(path) => Source::Image(path),
+		SavedSource::Color(hex) => Source::Color(rgb(&hex)?),
+		SavedSource::Preset(name) => Source::Preset(name),
+	};
+
+	let p = &state.palette;
+	let colors: Vec<Rgb> = p.colors.iter().map(|hex| rgb(hex)).collect::<Result<_>>()?;
+	let colors: [Rgb; 16] = colors
+		.try_into()
+		.map_err(|colors: Vec<Rgb>| anyhow!("expected 16 colors, found {}", colors.len()))?;
+	let palette = Palette::new(
+		colors,
+		rgb(&p.accent)?,
+		rgb(&p.accent_dim)?,
+		rgb(&p.accent_bright)?,
+		rgb(&p.secondary)?,
+		rgb(&p.surface)?,
+		rgb(&p.on_accent)?,
+		rgb(&p.on_surface)?,
+	);
+
+	Ok(Theme { palette, light_mode, source, generator })
+}
@@ -1,111 +1,594 @@
+pub mod builtin;
+mod custom;
+mod filter;
+mod format;
+mod include;
+mod parser;
+mod validate;
+
+pub use format::{Format, Value};
+pub use parser::{Error, Pos};
+
+use crate::base16;
 use crate::color::{Palette, Rgb};
-use crate::config::{expand_path, Config};
-use anyhow::{Context, Result};
-use std::fs;
-use std::process::Command;
+use crate::config::{expand_path, Config, TemplateConfig};
+use crate::hook;
+use crate::output::{self, Writer};
+use crate::theme::{Generator, Source, Theme};
+use anyhow::{anyhow, Context as _, Result};
+use parser::{Attr, Cond, Expr, Node, Operand};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::thread;
 
-pub fn process_templates(config: &Config, palette: &Palette, dry_run: bool) -> Result<()> {
-	for (name, template) in &config.templates {
-		let input = expand_path(&template.input_path);
-		let output = expand_path(&template.output_path);
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+	pub dry_run: bool,
+	// Write outputs and run hooks even when nothing changed.
+	pub force: bool,
+	// Print a diff of each changed output (always done in dry runs).
+	pub diff: bool,
+	// Don't print status lines such as `Wrote:`, so stdout only holds
+	// requested output like diffs.
+	pub quiet: bool,
+}
 
-		if !input.exists() {
-			eprintln!("Template not found: {} ({})", name, input.display());
-			continue;
+pub fn process_templates(config: &Config, theme: &Theme, options: Options) -> Result<()> {
+	let status = |message: String| {
+		if !options.quiet {
+			println!("{}", message);
 		}
+	};
+	let templates = config.ordered_templates()?;
+	let templates_dir = config.config.templates_dir.as_deref().map(expand_path);
 
-		let content = fs::read_to_string(&input)
-			.with_context(|| format!("Failed to read template: {}", input.display()))?;
+	// The theme regenerated in the other mode, for templates that ask for it.
+	let wants_other = |template: &TemplateConfig| template.mode.is_some_and(|mode| mode.is_light() != theme.light_mode);
+	let other_mode = match templates.iter().any(|(_, template)| wants_other(template)) {
+		true => Some(Theme::generate(theme.source.clone(), !theme.light_mode)?),
+		false => None,
+	};
 
-		let processed = replace_variables(&content, palette);
+	// `[colors]` is resolved once per theme, so a mistake in it is reported
+	// once rather than by every template.
+	let base = Context::new(theme).with_colors(&config.colors).context("Invalid [colors] in config")?;
+	let other_base = match &other_mode {
+		Some(other) => Some(Context::new(other).with_colors(&config.colors).context("Invalid [colors] in config")?),
+		None => None,
+	};
 
-		if dry_run {
-			println!("[dry-run] Would write: {}", output.display());
-		} else {
-			if let Some(parent) = output.parent() {
-				fs::create_dir_all(parent)?;
-			}
-			fs::write(&output, &processed)
-				.with_context(|| format!("Failed to write: {}", output.display()))?;
-			println!("Wrote: {}", output.display());
+	// Templates are rendered concurrently, and all of them before anything is
+	// written, so a broken template leaves every output untouched.
+	let results = thread::scope(|s| {
+		let handles: Vec<_> = templates
+			.iter()
+			.map(|&(name, template)| {
+				let (theme, base) = match (&other_mode, &other_base) {
+					(Some(other), Some(other_base)) if wants_other(template) => (other, other_base),
+					_ => (theme, &base),
+				};
+				let templates_dir = templates_dir.as_deref();
+				s.spawn(move || render_template(config, name, template, theme, base, templates_dir, options))
+			})
+			.collect();
+		join_all(handles)
+	});
+	let rendered: Vec<Rendered> = collect_errors(&templates, results)?.into_iter().flatten().collect();
 
-			if let Some(hook) = &template.post_hook {
-				run_hook(hook)?;
+	if options.dry_run {
+		for r in &rendered {
+			if r.changed {
+				status(format!("[dry-run] Would write: {}", r.output.display()));
+				print!("{}", r.diff.as_deref().unwrap_or_default());
+			} else {
+				status(format!("[dry-run] Unchanged: {}", r.output.display()));
 			}
 		}
+		return Ok(());
+	}
+
+	for r in &rendered {
+		match &r.diff {
+			_ if !r.changed => status(format!("Unchanged: {}", r.output.display())),
+			Some(diff) => print!("{}", diff),
+			None => {}
+		}
+	}
+
+	let changed: Vec<&Rendered> = rendered.iter().filter(|r| r.changed).collect();
+
+	// Pre-hooks run before anything is written, so an aborting one leaves
+	// every output as it was.
+	if (!changed.is_empty() || options.force) && let Some(hook) = &config.config.pre_hook {
+		status(format!("Running hook: {}", hook.command));
+		hook::run(hook, &hook::env(theme, None))?;
+	}
+	for r in &changed {
+		if let Some(hook) = &r.template.pre_hook {
+			status(format!("Running hook: {}", hook.command));
+			hook::run(hook, &hook::env(r.theme, Some(&r.output)))?;
+		}
+	}
+
+	let writer = Writer::new(config.config.backup.unwrap_or(true));
+	let results = thread::scope(|s| {
+		let handles: Vec<_> = changed
+			.iter()
+			.map(|r| {
+				let writer = &writer;
+				s.spawn(move || writer.write(&r.output, r.content.as_bytes()))
+			})
+			.collect();
+		join_all(handles)
+	});
+	let names: Vec<_> = changed.iter().map(|r| (r.name, r.template)).collect();
+	let abort = |e: anyhow::Error| {
+		if config.config.rollback {
+			writer.rollback();
+		}
+		e
+	};
+	collect_errors(&names, results).map_err(abort)?;
+
+	// Hooks run one at a time, in template order.
+	for r in &changed {
+		status(format!("Wrote: {}", r.output.display()));
+		if let Some(hook) = &r.template.post_hook {
+			status(format!("Running hook: {}", hook.command));
+			hook::run(hook, &hook::env(r.theme, Some(&r.output))).map_err(abort)?;
+		}
 	}
 
-	if !dry_run && let Some(hook) = &config.config.post_hook {
-		run_hook(hook)?;
+	if (!changed.is_empty() || options.force) && let Some(hook) = &config.config.post_hook {
+		status(format!("Running hook: {}", hook.command));
+		hook::run(hook, &hook::env(theme, None)).map_err(abort)?;
 	}
 
 	Ok(())
 }
 
-fn replace_variables(content: &str, palette: &Palette) -> String {
-	let mut result = content.to_string();
-
-	let vars: [(&str, Rgb); 25] = [
-		("background", palette.background()),
-		("foreground", palette.foreground()),
-		("color0", palette.colors[0]),
-		("color1", palette.colors[1]),
-		("color2", palette.colors[2]),
-		("color3", palette.colors[3]),
-		("color4", palette.colors[4]),
-		("color5", palette.colors[5]),
-		("color6", palette.colors[6]),
-		("color7", palette.colors[7]),
-		("color8", palette.colors[8]),
-		("color9", palette.colors[9]),
-		("color10", palette.colors[10]),
-		("color11", palette.colors[11]),
-		("color12", palette.colors[12]),
-		("color13", palette.colors[13]),
-		("color14", palette.colors[14]),
-		("color15", palette.colors[15]),
-		("accent", palette.accent),
-		("accent_dim", palette.accent_dim),
-		("accent_bright", palette.accent_bright),
-		("secondary", palette.secondary),
-		("surface", palette.surface),
-		("on_accent", palette.on_accent),
-		("on_surface", palette.on_surface),
-	];
-
-	for (name, color) in vars {
-		result = result.replace(&format!("{{{}.strip}}", name), &color.to_hex_strip());
-		result = result.replace(&format!("{{{}.rgb}}", name), &color.to_rgb_string());
-		result = result.replace(&format!("{{{}.rgba}}", name), &color.to_rgba_string(1.0));
-
-		let rgba_pattern = format!("{{{}.rgba:", name);
-		while let Some(start) = result.find(&rgba_pattern) {
-			let after_pattern = start + rgba_pattern.len();
-			if let Some(end) = result[after_pattern..].find('}') {
-				let alpha_str = &result[after_pattern..after_pattern + end];
-				if let Ok(alpha) = alpha_str.trim().parse::<f64>() {
-					let full_pattern = format!("{{{}.rgba:{}}}", name, alpha_str);
-					result = result.replace(&full_pattern, &color.to_rgba_string(alpha));
-				} else {
-					break;
+struct Rendered<'a> {
+	name: &'a str,
+	template: &'a TemplateConfig,
+	theme: &'a Theme,
+	output: PathBuf,
+	content: String,
+	changed: bool,
+	diff: Option<String>,
+}
+
+// Renders one template, or returns None if its input file is missing.
+fn render_template<'a>(
+	config: &Config,
+	name: &'a str,
+	template: &'a TemplateConfig,
+	theme: &'a Theme,
+	base: &Context,
+	templates_dir: Option<&Path>,
+	options: Options,
+) -> Result<Option<Rendered<'a>>> {
+	let input = input_path(template)?;
+	let output = expand_path(&template.output_path);
+
+	if builtin::source(&input).is_none() && !input.exists() {
+		eprintln!("Template not found: {} ({})", name, input.display());
+		return Ok(None);
+	}
+
+	let source = include::read(&input)
+		.with_context(|| format!("Failed to read template: {}", input.display()))?;
+	let ctx = template_context(config, name, template, theme, base)?;
+	let content = render(&input, &source, &ctx, templates_dir)?;
+
+	let changed = options.force || !output::is_current(&output, content.as_bytes());
+	let diff = (changed && (options.dry_run || options.diff)).then(|| {
+		let color = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+		output::diff(&output, &content, color)
+	});
+
+	Ok(Some(Rendered { name, template, theme, output, content, changed, diff }))
+}
+
+fn join_all<T>(handles: Vec<thread::ScopedJoinHandle<'_, Result<T>>>) -> Vec<Result<T>> {
+	handles
+		.into_iter()
+		.map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow!("thread panicked"))))
+		.collect()
+}
+
+// Returns the values if every template succeeded, or one error listing all
+// failures otherwise.
+fn collect_errors<T>(templates: &[(&str, &TemplateConfig)], results: Vec<Result<T>>) -> Result<Vec<T>> {
+	let mut values = Vec::new();
+	let mut errors = Vec::new();
+	for ((name, _), result) in templates.iter().zip(results) {
+		match result {
+			Ok(value) => values.push(value),
+			Err(e) => errors.push(format!("  {}: {:#}", name, e)),
+		}
+	}
+
+	match errors.len() {
+		0 => Ok(values),
+		n => Err(anyhow!("{} template{} failed:\n{}", n, if n == 1 { "" } else { "s" }, errors.join("\n"))),
+	}
+}
+
+const ANSI_NAMES: [&str; 16] = [
+	"black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+	"bright_black", "bright_red", "bright_green", "bright_yellow",
+	"bright_blue", "bright_magenta", "bright_cyan", "bright_white",
+];
+
+// Checks every configured template without writing anything, returning the
+// problems found as (template name, message) pairs.
+pub fn validate_templates(config: &Config) -> Vec<(String, String)> {
+	let mut problems = Vec::new();
+	let templates_dir = config.config.templates_dir.as_deref().map(expand_path);
+	// Names and kinds of variables do not depend on the palette.
+	let theme = Theme {
+		palette: Palette::default(),
+		light_mode: false,
+		source: Source::Preset("tokyo-night".to_string()),
+		generator: Generator::Preset,
+	};
+
+	let colors_valid = match Context::new(&theme).with_colors(&config.colors) {
+		Ok(_) => true,
+		Err(e) => {
+			problems.push(("[colors]".to_string(), e.to_string()));
+			false
+		}
+	};
+
+	let templates = config.ordered_templates().unwrap_or_else(|e| {
+		problems.push(("[templates]".to_string(), e.to_string()));
+		config.templates.iter().map(|(name, template)| (name.as_str(), template)).collect()
+	});
+	for (name, template) in templates {
+		let mut report = |message: String| problems.push((name.to_string(), message));
+
+		if let Err(e) = validate::check_output(&expand_path(&template.output_path)) {
+			report(e);
+		}
+
+		// An invalid [colors] table is reported once above; keep checking
+		// against the overrides alone.
+		let defs = if colors_valid { template_colors(config, template) } else { template.overrides.clone() };
+		let ctx = match Context::new(&theme).with_colors(&defs) {
+			Ok(ctx) => ctx,
+			Err(e) => {
+				report(format!("Invalid overrides: {}", e));
+				Context::new(&theme).with_colors(&config.colors).unwrap_or_else(|_| Context::new(&theme))
+			}
+		};
+		let ctx = ctx.with_vars(&template.vars);
+
+		let input = match input_path(template) {
+			Ok(input) => input,
+			Err(e) => {
+				report(e.to_string());
+				continue;
+			}
+		};
+		let content = match include::read(&input) {
+			Ok(content) => content,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => {
+				report(format!("Template not found: {}", input.display()));
+				continue;
+			}
+			Err(e) => {
+				report(format!("Failed to read template: {} ({})", input.display(), e));
+				continue;
+			}
+		};
+
+		// Syntax errors don't stop the checks, so everything is reported in
+		// one go, in file and line order.
+		let (nodes, mut errors) = include::load_all(&input, &content, templates_dir.as_deref());
+		errors.extend(validate::check(&nodes, &ctx).into_iter().map(|e| e.in_file(&input)));
+		errors.sort_by(|a, b| (&a.file, a.pos.line, a.pos.column).cmp(&(&b.file, b.pos.line, b.pos.column)));
+		for error in errors {
+			report(error.to_string());
+		}
+	}
+
+	problems
+}
+
+// Resolves `input_path`, mapping `builtin:` names to the embedded templates.
+fn input_path(template: &TemplateConfig) -> Result<PathBuf> {
+	match template.input_path.strip_prefix(builtin::PREFIX) {
+		Some(name) => builtin::path(name)
+			.ok_or_else(|| anyhow!("Unknown built-in template: {} (see `tinte templates list`)", name)),
+		None => Ok(expand_path(&template.input_path)),
+	}
+}
+
+// `base` is the theme's context with `[colors]` already applied; templates
+// with overrides resolve them together with `[colors]` instead.
+fn template_context(
+	config: &Config,
+	name: &str,
+	template: &TemplateConfig,
+	theme: &Theme,
+	base: &Context,
+) -> Result<Context> {
+	let ctx = match template.overrides.is_empty() {
+		true => base.clone(),
+		false => Context::new(theme)
+			.with_colors(&template_colors(config, template))
+			.with_context(|| format!("Invalid overrides for template: {}", name))?,
+	};
+	Ok(ctx.with_vars(&template.vars))
+}
+
+// `[colors]` and a template's `overrides` are resolved as one table, so an
+// override replaces a `[colors]` definition of the same name, and custom
+// colors derived from `accent` follow an overridden accent.
+fn template_colors(config: &Config, template: &TemplateConfig) -> HashMap<String, String> {
+	let mut defs = config.colors.clone();
+	defs.extend(template.overrides.iter().map(|(name, def)| (name.clone(), def.clone())));
+	defs
+}
+
+#[derive(Clone)]
+pub struct Context {
+	colors: HashMap<String, Value>,
+	text: HashMap<String, String>,
+	lists: HashMap<&'static str, Vec<Item>>,
+}
+
+// An element of a list iterated by `{% for %}`.
+#[derive(Clone)]
+struct Item {
+	index: usize,
+	name: String,
+	// The variable this item aliases, e.g. `color1` for `red`.
+	key: String,
+	color: Value,
+}
+
+enum Var<'a> {
+	Color(Value),
+	Text(&'a str),
+	Item(&'a Item),
+}
+
+impl Context {
+	pub fn new(theme: &Theme) -> Self {
+		let palette = &theme.palette;
+		let mut colors = HashMap::new();
+		let mut insert = |name: String, color| colors.insert(name, Value::opaque(color));
+		insert("background".to_string(), palette.background());
+		insert("foreground".to_string(), palette.foreground());
+		for (i, color) in palette.colors.iter().chain(palette.extended().iter()).enumerate() {
+			insert(format!("color{}", i), *color);
+		}
+		for (name, color) in palette.semantic() {
+			insert(name.to_string(), color);
+		}
+		for (name, color) in base16::NAMES.into_iter().zip(base16::base24(palette)) {
+			insert(name.to_string(), color);
+		}
+
+		let dark = (!theme.light_mode).to_string();
+		let light = theme.light_mode.to_string();
+		let text = HashMap::from([
+			("mode".to_string(), theme.mode().to_string()),
+			("dark".to_string(), dark.clone()),
+			("is_dark".to_string(), dark),
+			("light".to_string(), light.clone()),
+			("is_light".to_string(), light),
+			("source".to_string(), theme.source.to_string()),
+			("generator".to_string(), theme.generator.name().to_string()),
+		]);
+
+		let item = |index, name: &str, color| {
+			Item { index, name: name.to_string(), key: name.to_string(), color: Value::opaque(color) }
+		};
+		let all: Vec<Item> = palette.colors.iter()
+			.chain(palette.extended().iter())
+			.enumerate()
+			.map(|(i, c)| {
+				let key = format!("color{}", i);
+				match ANSI_NAMES.get(i) {
+					Some(name) => Item { key, ..item(i, name, *c) },
+					None => item(i, &key, *c),
 				}
-			} else {
-				break;
+			})
+			.collect();
+		let surfaces = palette.surfaces.iter().enumerate()
+			.map(|(i, c)| item(i, &format!("surface{}", i), *c))
+			.collect();
+		let semantic = palette.semantic().into_iter().enumerate()
+			.map(|(i, (name, c))| item(i, name, c))
+			.collect();
+		let base24: Vec<Item> = base16::NAMES.into_iter().zip(base16::base24(palette)).enumerate()
+			.map(|(i, (name, c))| item(i, name, c))
+			.collect();
+		let lists = HashMap::from([
+			("ansi", all[..16].to_vec()),
+			("extended", all[16..].to_vec()),
+			("base16", base24[..16].to_vec()),
+			("semantic", semantic),
+			("surfaces", surfaces),
+			("base24", base24),
+			("colors", all),
+		]);
+
+		Self { colors, text, lists }
+	}
+
+	// Adds the colors defined in the config's `[colors]` table, replacing
+	// built-in colors (and the loop items aliasing them) of the same name.
+	// `background` and `foreground` stay in sync with `color0` and `color15`.
+	pub fn with_colors(mut self, defs: &HashMap<String, String>) -> Result<Self> {
+		let defined = custom::resolve(defs, |name| self.colors.get(name).copied()).map_err(|e| anyhow!(e))?;
+		for (name, value) in defined {
+			let alias = match name.as_str() {
+				"background" => Some("color0"),
+				"color0" => Some("background"),
+				"foreground" => Some("color15"),
+				"color15" => Some("foreground"),
+				_ => None,
+			};
+			let keys = [Some(name.as_str()), alias];
+			for item in self.lists.values_mut().flatten().filter(|item| keys.contains(&Some(item.key.as_str()))) {
+				item.color = value;
+			}
+			if let Some(alias) = alias {
+				self.colors.insert(alias.to_string(), value);
 			}
+			self.colors.insert(name, value);
+		}
+		Ok(self)
+	}
+
+	pub fn with_vars(mut self, vars: &HashMap<String, String>) -> Self {
+		self.text.extend(vars.iter().map(|(name, value)| (name.clone(), value.clone())));
+		self
+	}
+
+	pub fn color(&self, name: &str) -> Option<Rgb> {
+		self.colors.get(name).map(|value| value.color)
+	}
+
+	fn lookup(&self, name: &str) -> Option<Var<'_>> {
+		match self.text.get(name) {
+			Some(text) => Some(Var::Text(text)),
+			None => self.colors.get(name).copied().map(Var::Color),
+		}
+	}
+}
+
+// Loop variables layered over the context, innermost last.
+struct Scope<'a> {
+	ctx: &'a Context,
+	locals: Vec<(&'a str, &'a Item)>,
+}
+
+impl Scope<'_> {
+	fn color(&self, name: &str) -> Option<Rgb> {
+		match self.local(name) {
+			Some(item) => Some(item.color.color),
+			None => self.ctx.color(name),
+		}
+	}
+
+	fn lookup(&self, name: &str) -> Option<Var<'_>> {
+		match self.local(name) {
+			Some(item) => Some(Var::Item(item)),
+			None => self.ctx.lookup(name),
 		}
+	}
 
-		result = result.replace(&format!("{{{}}}", name), &color.to_hex());
+	fn local(&self, name: &str) -> Option<&Item> {
+		self.locals.iter().rev().find(|(var, _)| *var == name).map(|(_, item)| *item)
 	}
+}
 
-	result
+pub fn render(path: &Path, source: &str, ctx: &Context, templates_dir: Option<&Path>) -> Result<String, Error> {
+	let nodes = include::load(path, source, templates_dir)?;
+	let mut out = String::with_capacity(source.len());
+	let mut scope = Scope { ctx, locals: Vec::new() };
+	render_nodes(&nodes, &mut scope, &mut out).map_err(|e| e.in_file(path))?;
+	Ok(out)
 }
 
-fn run_hook(cmd: &str) -> Result<()> {
-	println!("Running hook: {}", cmd);
-	Command::new("sh")
-		.arg("-c")
-		.arg(cmd)
-		.status()
-		.with_context(|| format!("Failed to run hook: {}", cmd))?;
+fn render_nodes<'a>(nodes: &'a [Node], scope: &mut Scope<'a>, out: &mut String) -> Result<(), Error> {
+	for node in nodes {
+		match node {
+			Node::Text(text) => out.push_str(text),
+			Node::Expr(expr) => out.push_str(&render_expr(expr, scope)?),
+			Node::If(block) => {
+				let mut taken = None;
+				for (cond, body) in &block.branches {
+					if eval(cond, scope)? {
+						taken = Some(body);
+						break;
+					}
+				}
+				render_nodes(taken.unwrap_or(&block.otherwise), scope, out)?;
+			}
+			Node::For(block) => {
+				let items = scope.ctx.lists.get(block.list.as_str()).ok_or_else(|| {
+					let mut names: Vec<_> = scope.ctx.lists.keys().copied().collect();
+					names.sort();
+					Error::new(block.pos, format!("unknown list `{}` (expected one of: {})", block.list, names.join(", ")))
+				})?;
+				for item in items {
+					scope.locals.push((&block.var, item));
+					render_nodes(&block.body, scope, out)?;
+					scope.locals.pop();
+				}
+			}
+			Node::Include(include) => {
+				render_nodes(&include.nodes, scope, out).map_err(|e| e.in_file(&include.file))?;
+			}
+		}
+	}
 	Ok(())
 }
+
+fn render_expr(expr: &Expr, scope: &Scope) -> Result<String, Error> {
+	let var = scope.lookup(&expr.name);
+	if let Some(attr) = expr.attr {
+		let Some(Var::Item(item)) = var else {
+			let attr = if attr == Attr::Index { "index" } else { "name" };
+			return Err(Error::new(expr.pos, format!("`.{}` is only available on loop variables", attr)));
+		};
+		return Ok(match attr {
+			Attr::Index => item.index.to_string(),
+			Attr::Name => item.name.clone(),
+		});
+	}
+
+	let mut value = match var {
+		Some(Var::Color(value)) => value,
+		Some(Var::Item(item)) => item.color,
+		Some(Var::Text(text)) => {
+			if expr.format.is_some() || !expr.filters.is_empty() {
+				return Err(Error::new(
+					expr.pos,
+					format!("`{}` is not a color; modifiers and filters only apply to colors", expr.name),
+				));
+			}
+			return Ok(text.to_string());
+		}
+		None => return Err(Error::new(expr.pos, format!("unknown variable `{}`", expr.name))),
+	};
+
+	for filter in &expr.filters {
+		value = filter
+			.apply(value, |name| scope.color(name))
+			.map_err(|e| Error::new(expr.pos, e))?;
+	}
+	Ok(expr.format.unwrap_or(Format::Hex).apply(value))
+}
+
+fn eval(cond: &Cond, scope: &Scope) -> Result<bool, Error> {
+	let value = |operand: &Operand, pos: Pos| match operand {
+		Operand::Str(s) => Ok(s.clone()),
+		Operand::Var(name) => match scope.lookup(name) {
+			Some(Var::Text(text)) => Ok(text.to_string()),
+			Some(Var::Color(value)) => Ok(Format::Hex.apply(value)),
+			Some(Var::Item(item)) => Ok(Format::Hex.apply(item.color)),
+			None => Err(Error::new(pos, format!("unknown variable `{}`", name))),
+		},
+	};
+
+	Ok(match cond {
+		Cond::Invalid => false,
+		Cond::Var(name, pos) => match scope.lookup(name) {
+			Some(Var::Text(text)) => !matches!(text, "" | "false" | "0"),
+			Some(Var::Color(_) | Var::Item(_)) => true,
+			None => return Err(Error::new(*pos, format!("unknown variable `{}`", name))),
+		},
+		Cond::Eq(left, right, pos) => value(left, *pos)? == value(right, *pos)?,
+		Cond::Not(inner) => !eval(inner, scope)?,
+		Cond::And(a, b) => eval(a, scope)? && eval(b, scope)?,
+		Cond::Or(a, b) => eval(a, scope)? || eval(b, scope)?,
+	})
+}
@@ -0,0 +1,53 @@
+// Templates shipped in `templates/`, embedded so configs can use
+// `input_path = "builtin:kitty"` without copying files.
+
+use std::path::{Path, PathBuf};
+
+pub const PREFIX: &str = "builtin:";
+
+const FILES: &[(&str, &str)] = &[
+	("btop.theme", include_str!("../../templates/btop.theme")),
+	("ghostty.conf", include_str!("../../templates/ghostty.conf")),
+	("gtk.css", include_str!("../../templates/gtk.css")),
+	("hyprland.conf", include_str!("../../templates/hyprland.conf")),
+	("hyprlock.conf", include_str!("../../templates/hyprlock.conf")),
+	("kitty.conf", include_str!("../../templates/kitty.conf")),
+	("mako.ini", include_str!("../../templates/mako.ini")),
+	("neovim.lua", include_str!("../../templates/neovim.lua")),
+	("niri.kdl", include_str!("../../templates/niri.kdl")),
+	("swayosd.css", include_str!("../../templates/swayosd.css")),
+	("vicinae.toml", include_str!("../../templates/vicinae.toml")),
+	("walker.css", include_str!("../../templates/walker.css")),
+	("waybar.css", include_str!("../../templates/waybar.css")),
+	("wofi.css", include_str!("../../templates/wofi.css")),
+	("partials/gtk-colors.css", include_str!("../../templates/partials/gtk-colors.css")),
+];
+
+// Names (file names without extension) and file names of all built-ins,
+// partials included.
+pub fn list() -> impl Iterator<Item = (&'static str, &'static str)> {
+	FILES.iter().map(|(file, _)| (file.split_once('.').map_or(*file, |(stem, _)| stem), *file))
+}
+
+// Looks up a file by name with or without extension, e.g. `kitty`,
+// `kitty.conf` or `partials/gtk-colors`, returning its file name and content.
+pub fn find(name: &str) -> Option<(&'static str, &'static str)> {
+	FILES.iter().copied().find(|(file, _)| {
+		*file == name || file.split_once('.').is_some_and(|(stem, _)| stem == name)
+	})
+}
+
+// The path a built-in file is reported under, e.g. `builtin:kitty.conf`.
+pub fn path(name: &str) -> Option<PathBuf> {
+	find(name).map(|(file, _)| PathBuf::from(format!("{}{}", PREFIX, file)))
+}
+
+// The file name inside a path returned by `path`.
+pub fn name(path: &Path) -> Option<&str> {
+	path.to_str()?.strip_prefix(PREFIX)
+}
+
+// Content of a path returned by `path`, or None for files on disk.
+pub fn source(path: &Path) -> Option<&'static str> {
+	find(name(path)?).map(|(_, content)| content)
+}
@@ -0,0 +1,170 @@
+use super::filter::Filter;
+use super::format::Value;
+use std::collections::HashMap;
+
+// Colors defined in the config's `[colors]` table or a template's
+// `overrides`: a color name or hex literal followed by filters, e.g.
+// `accent | mix background 0.5` or `#00000080`. Definitions may refer to each
+// other; a definition that names itself refers to the color it overrides.
+struct Def<'a> {
+	base: Base<'a>,
+	filters: Vec<Filter>,
+}
+
+enum Base<'a> {
+	Named(&'a str),
+	Literal(Value),
+}
+
+pub fn resolve(
+	defs: &HashMap<String, String>,
+	builtin: impl Fn(&str) -> Option<Value>,
+) -> Result<Vec<(String, Value)>, String> {
+	let mut parsed = HashMap::new();
+	for (name, src) in defs {
+		let def = parse(src).map_err(|e| format!("{}: {}", name, e))?;
+		parsed.insert(name.as_str(), def);
+	}
+
+	let mut names: Vec<&str> = parsed.keys().copied().collect();
+	names.sort();
+
+	let mut resolver = Resolver { defs: &parsed, builtin, done: HashMap::new(), stack: Vec::new() };
+	for name in &names {
+		resolver.eval(name).map_err(|e| format!("{}: {}", name, e))?;
+	}
+	Ok(names.into_iter().map(|name| (name.to_string(), resolver.done[name])).collect())
+}
+
+fn parse(src: &str) -> Result<Def<'_>, String> {
+	let mut parts = src.split('|');
+	let head = parts.next().unwrap_or_default().trim();
+	let base = if head.starts_with('#') {
+		Base::Literal(Value::from_hex(head).ok_or_else(|| format!("invalid hex color `{}`", head))?)
+	} else if !head.is_empty() && head.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		Base::Named(head)
+	} else {
+		return Err(format!("expected a color name or hex color, got `{}`", head));
+	};
+
+	let mut filters = Vec::new();
+	for part in parts {
+		let mut words = part.split_whitespace();
+		let name = words.next().ok_or("empty filter")?;
+		let args: Vec<&str> = words.collect();
+		filters.push(Filter::parse(name, &args)?);
+	}
+	Ok(Def { base, filters })
+}
+
+struct Resolver<'a, F> {
+	defs: &'a HashMap<&'a str, Def<'a>>,
+	builtin: F,
+	done: HashMap<&'a str, Value>,
+	stack: Vec<&'a str>,
+}
+
+impl<'a, F: Fn(&str) -> Option<Value>> Resolver<'a, F> {
+	fn eval(&mut self, name: &'a str) -> Result<Value, String> {
+		if let Some(value) = self.done.get(name) {
+			return Ok(*value);
+		}
+		if self.stack.contains(&name) {
+			let start = self.stack.iter().position(|n| *n == name).unwrap_or(0);
+			let mut chain = self.stack[start..].to_vec();
+			chain.push(name);
+			return Err(format!("cycle: {}", chain.join(" -> ")));
+		}
+
+		let defs = self.defs;
+		let def = &defs[name];
+		self.stack.push(name);
+
+		let mut value = match def.base {
+			Base::Named(base) => self.lookup(base, name)?,
+			Base::Literal(value) => value,
+		};
+		let mut refs = HashMap::new();
+		for filter in &def.filters {
+			for r in filter.refs() {
+				refs.insert(r, self.lookup(r, name)?);
+			}
+		}
+		for filter in &def.filters {
+			value = filter.apply(value, |n| refs.get(n).map(|v| v.color))?;
+		}
+
+		self.stack.pop();
+		self.done.insert(name, value);
+		Ok(value)
+	}
+
+	fn lookup(&mut self, name: &'a str, from: &str) -> Result<Value, String> {
+		if name != from && self.defs.contains_key(name) {
+			return self.eval(name);
+		}
+		(self.builtin)(name).ok_or_else(|| format!("unknown variable `{}`", name))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::color::Rgb;
+
+	fn hex(hex: &str) -> Value {
+		Value::from_hex(hex).unwrap()
+	}
+
+	fn builtin(name: &str) -> Option<Value> {
+		match name {
+			"accent" => Some(hex("#ff0000")),
+			"background" => Some(hex("#000000")),
+			_ => None,
+		}
+	}
+
+	fn resolve_defs(defs: &[(&str, &str)]) -> Result<HashMap<String, Value>, String> {
+		let defs = defs.iter().map(|(name, src)| (name.to_string(), src.to_string())).collect();
+		resolve(&defs, builtin).map(|colors| colors.into_iter().collect())
+	}
+
+	#[test]
+	fn definitions_refer_to_each_other() {
+		let colors = resolve_defs(&[("c", "b"), ("b", "a | alpha 0.5"), ("a", "#102030")]).unwrap();
+		assert_eq!(colors["a"], hex("#102030"));
+		assert_eq!(colors["b"], Value { color: Rgb::new(0x10, 0x20, 0x30), alpha: 0.5 });
+		assert_eq!(colors["c"], colors["b"]);
+	}
+
+	#[test]
+	fn self_reference_is_the_overridden_color() {
+		let colors = resolve_defs(&[("accent", "accent | alpha 0.5"), ("dim", "background | mix accent 0.5")]).unwrap();
+		assert_eq!(colors["accent"], Value { color: Rgb::new(0xff, 0, 0), alpha: 0.5 });
+		// Other definitions see the new value.
+		assert_eq!(colors["dim"].color, Rgb::new(0x80, 0, 0));
+	}
+
+	#[test]
+	fn self_reference_in_a_filter_argument() {
+		let colors = resolve_defs(&[("accent", "background | mix accent 1")]).unwrap();
+		assert_eq!(colors["accent"], hex("#ff0000"));
+	}
+
+	#[test]
+	fn cycles_are_reported() {
+		assert_eq!(resolve_defs(&[("a", "b"), ("b", "a")]).unwrap_err(), "a: cycle: a -> b -> a");
+		assert_eq!(
+			resolve_defs(&[("a", "#000000 | mix c 0.5"), ("b", "a"), ("c", "b | lighten 10")]).unwrap_err(),
+			"a: cycle: a -> c -> b -> a"
+		);
+	}
+
+	#[test]
+	fn invalid_definitions() {
+		assert_eq!(resolve_defs(&[("a", "missing")]).unwrap_err(), "a: unknown variable `missing`");
+		assert_eq!(resolve_defs(&[("a", "#12345")]).unwrap_err(), "a: invalid hex color `#12345`");
+		assert_eq!(resolve_defs(&[("a", "accent |")]).unwrap_err(), "a: empty filter");
+		assert_eq!(resolve_defs(&[("a", "accent | blur 2")]).unwrap_err(), "a: unknown filter `blur`");
+	}
+}
@@ -0,0 +1,107 @@
+use super::format::{parse_alpha, Value};
+use crate::color::Rgb;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorRef {
+	Named(String),
+	Literal(Rgb),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+	Lighten(f64),
+	Saturate(f64),
+	Rotate(f64),
+	Mix(ColorRef, f64),
+	Alpha(f64),
+	ContrastText,
+}
+
+impl Filter {
+	pub fn parse(name: &str, args: &[&str]) -> Result<Self, String> {
+		let expect = |n: usize| {
+			if args.len() == n {
+				Ok(())
+			} else {
+				Err(format!("filter `{}` takes {} argument{}, got {}", name, n, if n == 1 { "" } else { "s" }, args.len()))
+			}
+		};
+
+		let filter = match name {
+			"lighten" | "darken" | "saturate" | "desaturate" | "rotate" => {
+				expect(1)?;
+				let amount = parse_number(args[0])?;
+				match name {
+					"lighten" => Filter::Lighten(amount),
+					"darken" => Filter::Lighten(-amount),
+					"saturate" => Filter::Saturate(amount),
+					"desaturate" => Filter::Saturate(-amount),
+					_ => Filter::Rotate(amount),
+				}
+			}
+			"mix" => {
+				expect(2)?;
+				let color = if args[0].starts_with('#') {
+					let rgb = Rgb::from_hex(args[0]).ok_or_else(|| format!("invalid hex color `{}`", args[0]))?;
+					ColorRef::Literal(rgb)
+				} else {
+					ColorRef::Named(args[0].to_string())
+				};
+				let amount = parse_number(args[1])?;
+				if !(0.0..=1.0).contains(&amount) {
+					return Err(format!("invalid mix amount `{}` (expected a number between 0 and 1)", args[1]));
+				}
+				Filter::Mix(color, amount)
+			}
+			"alpha" => {
+				expect(1)?;
+				Filter::Alpha(parse_alpha(args[0])?)
+			}
+			"contrast_text" => {
+				expect(0)?;
+				Filter::ContrastText
+			}
+			_ => return Err(format!("unknown filter `{}`", name)),
+		};
+
+		Ok(filter)
+	}
+
+	// Color names `apply` will look up.
+	pub fn refs(&self) -> Vec<&str> {
+		match self {
+			Filter::Mix(ColorRef::Named(name), _) => vec![name],
+			Filter::ContrastText => vec!["background", "foreground"],
+			_ => Vec::new(),
+		}
+	}
+
+	// `lookup` resolves color names for `mix`, and `background`/`foreground` for `contrast_text`.
+	pub fn apply(&self, value: Value, lookup: impl Fn(&str) -> Option<Rgb>) -> Result<Value, String> {
+		let resolve = |name: &str| lookup(name).ok_or_else(|| format!("unknown variable `{}`", name));
+		let color = value.color;
+
+		let value = match self {
+			Filter::Lighten(amount) => Value { color: color.lighten(*amount), ..value },
+			Filter::Saturate(amount) => Value { color: color.saturate(*amount), ..value },
+			Filter::Rotate(degrees) => Value { color: color.rotate(*degrees), ..value },
+			Filter::Mix(other, amount) => {
+				let other = match other {
+					ColorRef::Named(name) => resolve(name)?,
+					ColorRef::Literal(rgb) => *rgb,
+				};
+				Value { color: color.mix(other, *amount), ..value }
+			}
+			Filter::Alpha(alpha) => Value { alpha: *alpha, ..value },
+			Filter::ContrastText => {
+				Value::opaque(color.text_on(resolve("background")?, resolve("foreground")?))
+			}
+		};
+
+		Ok(value)
+	}
+}
+
+fn parse_number(s: &str) -> Result<f64, String> {
+	s.parse::<f64>().map_err(|_| format!("invalid number `{}`", s))
+}
@@ -0,0 +1,132 @@
+use crate::color::Rgb;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Value {
+	pub color: Rgb,
+	pub alpha: f64,
+}
+
+impl Value {
+	pub fn opaque(color: Rgb) -> Self {
+		Self { color, alpha: 1.0 }
+	}
+
+	// `#rrggbb` or `#rrggbbaa`.
+	pub fn from_hex(hex: &str) -> Option<Self> {
+		let digits = hex.trim_start_matches('#');
+		match digits.len() {
+			6 => Rgb::from_hex(digits).map(Self::opaque),
+			8 => {
+				let color = Rgb::from_hex(&digits[..6])?;
+				let alpha = u8::from_str_radix(&digits[6..], 16).ok()?;
+				// Three decimals keep every byte distinct while printing as `0.502`.
+				Some(Self { color, alpha: (alpha as f64 / 255.0 * 1000.0).round() / 1000.0 })
+			}
+			_ => None,
+		}
+	}
+
+	fn alpha_hex(self) -> String {
+		if self.alpha < 1.0 {
+			format!("{:02x}", (self.alpha * 255.0).round() as u8)
+		} else {
+			String::new()
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+	Hex,
+	Hexa(Option<f64>),
+	Strip,
+	Hex0x,
+	Argb(Option<f64>),
+	Rgb,
+	Rgba(Option<f64>),
+	Hsl,
+	Hsla(Option<f64>),
+	Oklch,
+	Float,
+	Int,
+	Red,
+	Green,
+	Blue,
+	AnsiFg,
+	AnsiBg,
+}
+
+impl Format {
+	pub fn parse(name: &str, arg: Option<&str>) -> Result<Self, String> {
+		let alpha = || arg.map(parse_alpha).transpose();
+		let format = match name {
+			"hex" => Format::Hex,
+			"hexa" => return Ok(Format::Hexa(alpha()?)),
+			"strip" => Format::Strip,
+			"0x" => Format::Hex0x,
+			"argb" => return Ok(Format::Argb(alpha()?)),
+			"rgb" => Format::Rgb,
+			"rgba" => return Ok(Format::Rgba(alpha()?)),
+			"hsl" => Format::Hsl,
+			"hsla" => return Ok(Format::Hsla(alpha()?)),
+			"oklch" => Format::Oklch,
+			"float" => Format::Float,
+			"int" => Format::Int,
+			"r" => Format::Red,
+			"g" => Format::Green,
+			"b" => Format::Blue,
+			"ansi_fg" => Format::AnsiFg,
+			"ansi_bg" => Format::AnsiBg,
+			_ => return Err(format!("unknown modifier `{}`", name)),
+		};
+
+		if arg.is_some() {
+			return Err(format!("modifier `{}` takes no argument", name));
+		}
+		Ok(format)
+	}
+
+	pub fn apply(self, value: Value) -> String {
+		let Rgb { r, g, b } = value.color;
+		let color = value.color;
+		let alpha_byte = |alpha: Option<f64>| (alpha.unwrap_or(value.alpha) * 255.0).round() as u8;
+
+		match self {
+			Format::Hex => format!("{}{}", color.to_hex(), value.alpha_hex()),
+			Format::Hexa(alpha) => format!("{}{:02x}", color.to_hex(), alpha_byte(alpha)),
+			Format::Strip => format!("{}{}", color.to_hex_strip(), value.alpha_hex()),
+			Format::Hex0x => format!("0x{}", color.to_hex_strip()),
+			Format::Argb(alpha) => format!("0x{:02x}{}", alpha_byte(alpha), color.to_hex_strip()),
+			Format::Rgb => color.to_rgb_string(),
+			Format::Rgba(alpha) => color.to_rgba_string(alpha.unwrap_or(value.alpha)),
+			Format::Hsl => {
+				let hsl = color.to_hsl();
+				format!("hsl({:.0}, {:.0}%, {:.0}%)", hsl.h, hsl.s * 100.0, hsl.l * 100.0)
+			}
+			Format::Hsla(alpha) => {
+				let hsl = color.to_hsl();
+				let alpha = alpha.unwrap_or(value.alpha);
+				format!("hsla({:.0}, {:.0}%, {:.0}%, {})", hsl.h, hsl.s * 100.0, hsl.l * 100.0, alpha)
+			}
+			Format::Oklch => {
+				let lch = color.to_oklch();
+				let alpha = if value.alpha < 1.0 { format!(" / {}", value.alpha) } else { String::new() };
+				format!("oklch({:.1}% {:.3} {:.1}{})", lch.l * 100.0, lch.c, lch.h, alpha)
+			}
+			Format::Float => format!("{:.3} {:.3} {:.3}", r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0),
+			Format::Int => ((r as u32) << 16 | (g as u32) << 8 | b as u32).to_string(),
+			Format::Red => r.to_string(),
+			Format::Green => g.to_string(),
+			Format::Blue => b.to_string(),
+			Format::AnsiFg => format!("\x1b[38;2;{};{};{}m", r, g, b),
+			Format::AnsiBg => format!("\x1b[48;2;{};{};{}m", r, g, b),
+		}
+	}
+}
+
+pub fn parse_alpha(s: &str) -> Result<f64, String> {
+	match s.trim().parse::<f64>() {
+		Ok(a) if (0.0..=1.0).contains(&a) => Ok(a),
+		_ => Err(format!("invalid alpha `{}` (expected a number between 0 and 1)", s)),
+	}
+}
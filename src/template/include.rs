@@ -0,0 +1,129 @@
+use super::builtin;
+use super::parser::{self, Error, Node, Pos};
+use crate::config::expand_path;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Parses a template and splices in the files named by its `{% include %}`
+// tags. Include paths are relative to the including file, falling back to
+// the configured templates directory and then the built-in templates, so
+// exported built-ins keep working without copying their partials.
+pub fn load(path: &Path, source: &str, templates_dir: Option<&Path>) -> Result<Vec<Node>, Error> {
+	let (nodes, errors) = load_all(path, source, templates_dir);
+	match errors.into_iter().next() {
+		None => Ok(nodes),
+		Some(error) => Err(error),
+	}
+}
+
+// Like `load`, but carries on past errors and returns all of them, each
+// attributed to the file it occurred in.
+pub fn load_all(path: &Path, source: &str, templates_dir: Option<&Path>) -> (Vec<Node>, Vec<Error>) {
+	let mut resolver = Resolver { templates_dir, stack: vec![canonical(path)], errors: Vec::new() };
+	let (mut nodes, errors) = parser::parse(source);
+	resolver.errors.extend(errors.into_iter().map(|e| e.in_file(path)));
+	resolver.resolve(&mut nodes, path);
+	(nodes, resolver.errors)
+}
+
+struct Resolver<'a> {
+	templates_dir: Option<&'a Path>,
+	// Files currently being included, outermost first.
+	stack: Vec<PathBuf>,
+	errors: Vec<Error>,
+}
+
+impl Resolver<'_> {
+	fn resolve(&mut self, nodes: &mut [Node], from: &Path) {
+		for node in nodes {
+			match node {
+				Node::Text(_) | Node::Expr(_) => {}
+				Node::If(block) => {
+					for (_, body) in &mut block.branches {
+						self.resolve(body, from);
+					}
+					self.resolve(&mut block.otherwise, from);
+				}
+				Node::For(block) => self.resolve(&mut block.body, from),
+				Node::Include(include) => {
+					let Some(file) = self.find(&include.path, from) else {
+						self.error(include.pos, format!("included file `{}` not found", include.path), from);
+						continue;
+					};
+
+					let key = canonical(&file);
+					if let Some(start) = self.stack.iter().position(|p| *p == key) {
+						let chain: Vec<String> = self.stack[start..]
+							.iter()
+							.chain([&key])
+							.map(|p| p.display().to_string())
+							.collect();
+						self.error(include.pos, format!("include cycle: {}", chain.join(" -> ")), from);
+						continue;
+					}
+
+					let source = match read(&file) {
+						Ok(source) => source,
+						Err(e) => {
+							self.error(include.pos, format!("failed to read `{}`: {}", file.display(), e), from);
+							continue;
+						}
+					};
+					let (mut nodes, errors) = parser::parse(&source);
+					self.errors.extend(errors.into_iter().map(|e| e.in_file(&file)));
+					self.stack.push(key);
+					self.resolve(&mut nodes, &file);
+					self.stack.pop();
+
+					include.file = file;
+					include.nodes = nodes;
+				}
+			}
+		}
+	}
+
+	fn error(&mut self, pos: Pos, message: String, file: &Path) {
+		self.errors.push(Error::new(pos, message).in_file(file));
+	}
+
+	fn find(&self, name: &str, from: &Path) -> Option<PathBuf> {
+		if let Some(name) = name.strip_prefix(builtin::PREFIX) {
+			return builtin::path(name);
+		}
+		// Built-in templates only include other built-ins.
+		if let Some(from) = builtin::name(from) {
+			let beside = Path::new(from).parent().unwrap_or(Path::new("")).join(name);
+			return beside.to_str().and_then(builtin::path);
+		}
+
+		let path = expand_path(name);
+		if path.is_absolute() {
+			return path.is_file().then_some(path);
+		}
+
+		let beside = from.parent().unwrap_or(Path::new("")).join(&path);
+		if beside.is_file() {
+			return Some(beside);
+		}
+		self.templates_dir
+			.map(|dir| dir.join(&path))
+			.filter(|p| p.is_file())
+			.or_else(|| builtin::path(name))
+	}
+}
+
+// Reads a template or partial from disk or from the built-in set.
+pub fn read(path: &Path) -> io::Result<String> {
+	match builtin::source(path) {
+		Some(source) => Ok(source.to_string()),
+		None => fs::read_to_string(path),
+	}
+}
+
+fn canonical(path: &Path) -> PathBuf {
+	if builtin::name(path).is_some() {
+		return path.to_path_buf();
+	}
+	fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,767 @@
+use super::filter::Filter;
+use super::format::Format;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pos {
+	pub line: usize,
+	pub column: usize,
+}
+
+#[derive(Debug)]
+pub struct Error {
+	pub file: Option<PathBuf>,
+	pub pos: Pos,
+	pub message: String,
+}
+
+impl Error {
+	pub fn new(pos: Pos, message: impl Into<String>) -> Self {
+		Self { file: None, pos, message: message.into() }
+	}
+
+	// Attributes the error to `file` unless it already came from a more
+	// deeply included one.
+	pub fn in_file(mut self, file: &Path) -> Self {
+		self.file.get_or_insert_with(|| file.to_path_buf());
+		self
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(file) = &self.file {
+			write!(f, "{}:", file.display())?;
+		}
+		write!(f, "{}:{}: {}", self.pos.line, self.pos.column, self.message)
+	}
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug)]
+pub enum Node {
+	Text(String),
+	Expr(Expr),
+	If(If),
+	For(For),
+	Include(Include),
+}
+
+#[derive(Debug)]
+pub struct Expr {
+	pub name: String,
+	pub attr: Option<Attr>,
+	pub format: Option<Format>,
+	pub filters: Vec<Filter>,
+	pub pos: Pos,
+}
+
+// Loop item attributes, written like modifiers: {c.index}, {c.name}.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attr {
+	Index,
+	Name,
+}
+
+#[derive(Debug)]
+pub struct If {
+	pub branches: Vec<(Cond, Vec<Node>)>,
+	pub otherwise: Vec<Node>,
+}
+
+#[derive(Debug)]
+pub struct For {
+	pub var: String,
+	pub list: String,
+	pub body: Vec<Node>,
+	pub pos: Pos,
+}
+
+// `file` and `nodes` are filled in by the include resolver after parsing.
+#[derive(Debug)]
+pub struct Include {
+	pub path: String,
+	pub pos: Pos,
+	pub file: PathBuf,
+	pub nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+pub enum Cond {
+	// Stands in for a condition that failed to parse, so the rest of the
+	// template can still be checked. Never rendered.
+	Invalid,
+	Var(String, Pos),
+	Eq(Operand, Operand, Pos),
+	Not(Box<Cond>),
+	And(Box<Cond>, Box<Cond>),
+	Or(Box<Cond>, Box<Cond>),
+}
+
+#[derive(Debug)]
+pub enum Operand {
+	Var(String),
+	Str(String),
+}
+
+struct Tag {
+	keyword: String,
+	args: String,
+	pos: Pos,
+	args_pos: Pos,
+}
+
+// Template syntax:
+//   {name}                 hex color or text variable
+//   {name.modifier}        formatted color, e.g. {accent.rgb}
+//   {name.modifier:arg}    formatted color with argument, e.g. {accent.rgba:0.5}
+//   {name | filter args}   transformed color, e.g. {accent | lighten 10 | alpha 0.5}
+//   {{                     literal `{`
+//   {% if cond %}...{% elif cond %}...{% else %}...{% endif %}
+//   {% for c in list %}...{c.index} {c.name} {c}...{% endfor %}
+//   {% include "partials/colors.css" %}
+//
+// A `{` only opens a token when followed by a letter or `_`, so CSS blocks and
+// Lua tables (`{` followed by whitespace or a quote) pass through unchanged.
+// A `{% %}` tag alone on its line removes the whole line from the output.
+//
+// Parsing carries on past malformed tokens and tags, skipping them, so every
+// syntax error is returned along with the nodes that did parse.
+pub fn parse(src: &str) -> (Vec<Node>, Vec<Error>) {
+	let mut parser = Parser { src, offset: 0, line: 1, line_start: 0, errors: Vec::new() };
+	let mut nodes = Vec::new();
+	loop {
+		let (block, end) = parser.parse_block();
+		nodes.extend(block);
+		match end {
+			None => break,
+			Some(tag) => parser.error(tag.pos, format!("unexpected `{{% {} %}}`", tag.keyword)),
+		}
+	}
+	(nodes, parser.errors)
+}
+
+struct Parser<'a> {
+	src: &'a str,
+	offset: usize,
+	line: usize,
+	line_start: usize,
+	errors: Vec<Error>,
+}
+
+impl Parser<'_> {
+	// Parses until end of input or a tag that closes the enclosing block,
+	// which is returned for the caller to handle.
+	fn parse_block(&mut self) -> (Vec<Node>, Option<Tag>) {
+		let mut nodes = Vec::new();
+		let mut text = String::new();
+
+		while let Some(c) = self.peek() {
+			let rest = self.rest();
+			if rest.starts_with("{{") {
+				text.push('{');
+				self.advance(2);
+			} else if rest.starts_with("{%") {
+				let standalone = self.src[self.line_start..self.offset].trim().is_empty();
+				let Some(tag) = self.parse_tag() else {
+					continue;
+				};
+				if standalone && self.rest_of_line().trim().is_empty() {
+					text.truncate(text.trim_end_matches([' ', '\t']).len());
+					let len = self.rest_of_line().len();
+					self.advance((len + 1).min(self.rest().len()));
+				}
+				if !text.is_empty() {
+					nodes.push(Node::Text(std::mem::take(&mut text)));
+				}
+
+				match tag.keyword.as_str() {
+					"if" => nodes.push(Node::If(self.parse_if(tag))),
+					"for" => nodes.push(Node::For(self.parse_for(tag))),
+					"include" => match parse_include(tag) {
+						Ok(include) => nodes.push(Node::Include(include)),
+						Err(e) => self.errors.push(e),
+					},
+					"elif" | "else" | "endif" | "endfor" => return (nodes, Some(tag)),
+					other => self.error(tag.pos, format!("unknown tag `{}`", other)),
+				}
+			} else if c == '{' && rest[1..].starts_with(is_ident_start) {
+				if !text.is_empty() {
+					nodes.push(Node::Text(std::mem::take(&mut text)));
+				}
+				if let Some(expr) = self.parse_expr() {
+					nodes.push(Node::Expr(expr));
+				}
+			} else {
+				text.push(c);
+				self.advance(c.len_utf8());
+			}
+		}
+
+		if !text.is_empty() {
+			nodes.push(Node::Text(text));
+		}
+		(nodes, None)
+	}
+
+	fn parse_if(&mut self, tag: Tag) -> If {
+		let mut branches = Vec::new();
+		let mut cond = self.parse_cond(&tag.args, tag.args_pos);
+		let mut body = Vec::new();
+		let mut in_else = false;
+
+		loop {
+			let (nodes, end) = self.parse_block();
+			body.extend(nodes);
+			let Some(end) = end else {
+				self.error(tag.pos, "`{% if %}` without matching `{% endif %}`");
+				break;
+			};
+
+			match end.keyword.as_str() {
+				"endif" => {
+					self.expect_no_args(&end);
+					break;
+				}
+				other if in_else => {
+					self.error(end.pos, format!("unexpected `{{% {} %}}` after `{{% else %}}`", other));
+				}
+				"elif" => {
+					let next = self.parse_cond(&end.args, end.args_pos);
+					branches.push((std::mem::replace(&mut cond, next), std::mem::take(&mut body)));
+				}
+				"else" => {
+					self.expect_no_args(&end);
+					branches.push((std::mem::replace(&mut cond, Cond::Invalid), std::mem::take(&mut body)));
+					in_else = true;
+				}
+				other => {
+					self.error(end.pos, format!("unexpected `{{% {} %}}` inside `{{% if %}}`", other));
+				}
+			}
+		}
+
+		if in_else {
+			If { branches, otherwise: body }
+		} else {
+			branches.push((cond, body));
+			If { branches, otherwise: Vec::new() }
+		}
+	}
+
+	fn parse_for(&mut self, tag: Tag) -> For {
+		let words: Vec<&str> = tag.args.split_whitespace().collect();
+		let (var, list) = match words[..] {
+			[var, "in", list] if is_ident(var) && is_ident(list) => (var.to_string(), list.to_string()),
+			_ => {
+				self.error(tag.args_pos, "expected `{% for <name> in <list> %}`");
+				// Keep the loop variable, if there is one, so the body is
+				// still checked without spurious errors.
+				let var = words.first().filter(|var| is_ident(var)).unwrap_or(&"");
+				(var.to_string(), String::new())
+			}
+		};
+
+		let mut body = Vec::new();
+		loop {
+			let (nodes, end) = self.parse_block();
+			body.extend(nodes);
+			match end {
+				Some(end) if end.keyword == "endfor" => {
+					self.expect_no_args(&end);
+					break;
+				}
+				Some(end) => {
+					self.error(end.pos, format!("unexpected `{{% {} %}}` inside `{{% for %}}`", end.keyword));
+				}
+				None => {
+					self.error(tag.pos, "`{% for %}` without matching `{% endfor %}`");
+					break;
+				}
+			}
+		}
+		For { var, list, body, pos: tag.args_pos }
+	}
+
+	fn parse_cond(&mut self, src: &str, pos: Pos) -> Cond {
+		parse_cond(src, pos).unwrap_or_else(|e| {
+			self.errors.push(e);
+			Cond::Invalid
+		})
+	}
+
+	fn expect_no_args(&mut self, tag: &Tag) {
+		if !tag.args.is_empty() {
+			self.error(tag.args_pos, format!("`{{% {} %}}` takes no arguments", tag.keyword));
+		}
+	}
+
+	fn parse_tag(&mut self) -> Option<Tag> {
+		let pos = self.pos();
+		let body_len = match self.rest()[2..].find("%}") {
+			Some(len) if !self.rest()[2..2 + len].contains('\n') => len,
+			_ => {
+				self.error(pos, "unterminated tag (expected `%}` on the same line)");
+				self.advance(2);
+				return None;
+			}
+		};
+		self.advance(2);
+
+		let body = &self.rest()[..body_len];
+		let leading = body.len() - body.trim_start().len();
+		let trimmed = body.trim();
+		let keyword_len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+		let keyword = trimmed[..keyword_len].to_string();
+		let args = trimmed[keyword_len..].trim_start();
+		let args_pos = self.pos_at(self.offset + leading + trimmed.len() - args.len());
+		let args = args.to_string();
+		self.advance(body_len + 2);
+
+		if keyword.is_empty() {
+			self.error(pos, "empty tag");
+			return None;
+		}
+		Some(Tag { keyword, args, pos, args_pos })
+	}
+
+	fn parse_expr(&mut self) -> Option<Expr> {
+		let pos = self.pos();
+		let body_len = match self.rest()[1..].find(['}', '\n']) {
+			Some(len) if self.rest()[1 + len..].starts_with('}') => len,
+			_ => {
+				self.error(pos, "unterminated token (write `{{` for a literal brace)");
+				self.advance(1);
+				return None;
+			}
+		};
+		self.advance(1);
+		let expr = self.expr_body(body_len, pos);
+		self.advance(body_len + 1);
+		expr.map_err(|e| self.errors.push(e)).ok()
+	}
+
+	// Parses the `body_len` bytes of a token after its `{`.
+	fn expr_body(&self, body_len: usize, pos: Pos) -> Result<Expr, Error> {
+		let body = &self.rest()[..body_len];
+		let (head, filters) = match body.split_once('|') {
+			Some((head, filters)) => (head, Some(filters)),
+			None => (body, None),
+		};
+		let head = head.trim_end();
+		let name_len = head.find(|c| !is_ident_char(c)).unwrap_or(head.len());
+		let name = head[..name_len].to_string();
+
+		let mut attr = None;
+		let format = match head[name_len..].strip_prefix('.') {
+			Some("index") => {
+				attr = Some(Attr::Index);
+				None
+			}
+			Some("name") => {
+				attr = Some(Attr::Name);
+				None
+			}
+			Some(modifier) => {
+				let modifier_pos = self.pos_at(self.offset + name_len + 1);
+				let (modifier, arg) = match modifier.split_once(':') {
+					Some((m, a)) => (m, Some(a)),
+					None => (modifier, None),
+				};
+				if modifier.is_empty() || !modifier.chars().all(is_ident_char) {
+					return Err(Error::new(modifier_pos, format!("malformed modifier in `{{{}}}`", body)));
+				}
+				Some(Format::parse(modifier, arg).map_err(|e| Error::new(modifier_pos, e))?)
+			}
+			None if name_len == head.len() => None,
+			None => {
+				let at = self.pos_at(self.offset + name_len);
+				return Err(Error::new(at, format!("malformed token `{{{}}}`", body)));
+			}
+		};
+
+		let mut parsed = Vec::new();
+		if let Some(filters) = filters {
+			let mut filter_offset = self.offset + body.len() - filters.len();
+			for filter in filters.split('|') {
+				let at = self.pos_at(filter_offset + filter.len() - filter.trim_start().len());
+				let mut words = filter.split_whitespace();
+				let filter_name = words
+					.next()
+					.ok_or_else(|| Error::new(at, format!("empty filter in `{{{}}}`", body)))?;
+				let args: Vec<&str> = words.collect();
+				parsed.push(Filter::parse(filter_name, &args).map_err(|e| Error::new(at, e))?);
+				filter_offset += filter.len() + 1;
+			}
+		}
+
+		if attr.is_some() && !parsed.is_empty() {
+			return Err(Error::new(pos, format!("filters cannot be applied to `{{{}}}`", head)));
+		}
+
+		Ok(Expr { name, attr, format, filters: parsed, pos })
+	}
+
+	fn error(&mut self, pos: Pos, message: impl Into<String>) {
+		self.errors.push(Error::new(pos, message));
+	}
+
+	fn rest(&self) -> &str {
+		&self.src[self.offset..]
+	}
+
+	fn rest_of_line(&self) -> &str {
+		let rest = self.rest();
+		&rest[..rest.find('\n').unwrap_or(rest.len())]
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.rest().chars().next()
+	}
+
+	fn advance(&mut self, len: usize) {
+		for (i, c) in self.src[self.offset..self.offset + len].char_indices() {
+			if c == '\n' {
+				self.line += 1;
+				self.line_start = self.offset + i + 1;
+			}
+		}
+		self.offset += len;
+	}
+
+	fn pos(&self) -> Pos {
+		self.pos_at(self.offset)
+	}
+
+	// Only valid for offsets on the current line.
+	fn pos_at(&self, offset: usize) -> Pos {
+		Pos { line: self.line, column: self.src[self.line_start..offset].chars().count() + 1 }
+	}
+}
+
+// Conditions:
+//   dark                   truthy variable
+//   not light
+//   generator == "subtle"  (also !=)
+//   a and b, a or b        `and` binds tighter than `or`
+fn parse_cond(src: &str, pos: Pos) -> Result<Cond, Error> {
+	let tokens = tokenize_cond(src, pos)?;
+	if tokens.is_empty() {
+		return Err(Error::new(pos, "missing condition"));
+	}
+
+	let mut parser = CondParser { tokens: &tokens, index: 0, pos };
+	let cond = parser.parse_or()?;
+	match parser.tokens.get(parser.index) {
+		None => Ok(cond),
+		Some((token, at)) => Err(Error::new(*at, format!("unexpected `{}` in condition", token.text()))),
+	}
+}
+
+#[derive(Debug, PartialEq)]
+enum CondToken {
+	Ident(String),
+	Str(String),
+	Eq,
+	Ne,
+}
+
+impl CondToken {
+	fn text(&self) -> String {
+		match self {
+			CondToken::Ident(s) => s.clone(),
+			CondToken::Str(s) => format!("\"{}\"", s),
+			CondToken::Eq => "==".to_string(),
+			CondToken::Ne => "!=".to_string(),
+		}
+	}
+}
+
+fn tokenize_cond(src: &str, pos: Pos) -> Result<Vec<(CondToken, Pos)>, Error> {
+	let mut tokens = Vec::new();
+	let mut chars = src.char_indices().peekable();
+	let at = |i: usize| Pos { line: pos.line, column: pos.column + src[..i].chars().count() };
+
+	while let Some(&(i, c)) = chars.peek() {
+		if c.is_whitespace() {
+			chars.next();
+		} else if c == '"' {
+			chars.next();
+			let mut value = String::new();
+			loop {
+				match chars.next() {
+					Some((_, '"')) => break,
+					Some((_, c)) => value.push(c),
+					None => return Err(Error::new(at(i), "unterminated string in condition")),
+				}
+			}
+			tokens.push((CondToken::Str(value), at(i)));
+		} else if src[i..].starts_with("==") || src[i..].starts_with("!=") {
+			chars.next();
+			chars.next();
+			let token = if c == '=' { CondToken::Eq } else { CondToken::Ne };
+			tokens.push((token, at(i)));
+		} else if is_ident_start(c) {
+			let mut ident = String::new();
+			while let Some(&(_, c)) = chars.peek() {
+				if !is_ident_char(c) {
+					break;
+				}
+				ident.push(c);
+				chars.next();
+			}
+			tokens.push((CondToken::Ident(ident), at(i)));
+		} else {
+			return Err(Error::new(at(i), format!("unexpected `{}` in condition", c)));
+		}
+	}
+
+	Ok(tokens)
+}
+
+struct CondParser<'a> {
+	tokens: &'a [(CondToken, Pos)],
+	index: usize,
+	pos: Pos,
+}
+
+impl CondParser<'_> {
+	fn parse_or(&mut self) -> Result<Cond, Error> {
+		let mut left = self.parse_and()?;
+		while self.eat_keyword("or") {
+			left = Cond::Or(Box::new(left), Box::new(self.parse_and()?));
+		}
+		Ok(left)
+	}
+
+	fn parse_and(&mut self) -> Result<Cond, Error> {
+		let mut left = self.parse_not()?;
+		while self.eat_keyword("and") {
+			left = Cond::And(Box::new(left), Box::new(self.parse_not()?));
+		}
+		Ok(left)
+	}
+
+	fn parse_not(&mut self) -> Result<Cond, Error> {
+		if self.eat_keyword("not") {
+			return Ok(Cond::Not(Box::new(self.parse_not()?)));
+		}
+
+		let (left, pos) = self.parse_operand()?;
+		let negate = match self.tokens.get(self.index) {
+			Some((CondToken::Eq, _)) => false,
+			Some((CondToken::Ne, _)) => true,
+			_ => {
+				return match left {
+					Operand::Var(name) => Ok(Cond::Var(name, pos)),
+					Operand::Str(s) => Err(Error::new(pos, format!("string \"{}\" is not a condition", s))),
+				};
+			}
+		};
+		self.index += 1;
+
+		let (right, _) = self.parse_operand()?;
+		let eq = Cond::Eq(left, right, pos);
+		Ok(if negate { Cond::Not(Box::new(eq)) } else { eq })
+	}
+
+	fn parse_operand(&mut self) -> Result<(Operand, Pos), Error> {
+		match self.tokens.get(self.index) {
+			Some((CondToken::Ident(name), pos)) if !matches!(name.as_str(), "and" | "or" | "not") => {
+				self.index += 1;
+				Ok((Operand::Var(name.clone()), *pos))
+			}
+			Some((CondToken::Str(s), pos)) => {
+				self.index += 1;
+				Ok((Operand::Str(s.clone()), *pos))
+			}
+			Some((token, pos)) => Err(Error::new(*pos, format!("unexpected `{}` in condition", token.text()))),
+			None => {
+				let pos = self.tokens.last().map(|(_, p)| *p).unwrap_or(self.pos);
+				Err(Error::new(pos, "incomplete condition"))
+			}
+		}
+	}
+
+	fn eat_keyword(&mut self, keyword: &str) -> bool {
+		match self.tokens.get(self.index) {
+			Some((CondToken::Ident(name), _)) if name == keyword => {
+				self.index += 1;
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
+fn parse_include(tag: Tag) -> Result<Include, Error> {
+	let path = tag.args.strip_prefix('"').and_then(|s| s.strip_suffix('"'));
+	match path {
+		Some(path) if !path.is_empty() && !path.contains('"') => Ok(Include {
+			path: path.to_string(),
+			pos: tag.args_pos,
+			file: PathBuf::new(),
+			nodes: Vec::new(),
+		}),
+		_ => Err(Error::new(tag.args_pos, "expected `{% include \"path\" %}`")),
+	}
+}
+
+fn is_ident(s: &str) -> bool {
+	s.starts_with(is_ident_start) && s.chars().all(is_ident_char)
+}
+
+fn is_ident_start(c: char) -> bool {
+	c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+	c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_ok(src: &str) -> Vec<Node> {
+		let (nodes, errors) = parse(src);
+		assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+		nodes
+	}
+
+	fn errors(src: &str) -> Vec<(usize, usize, String)> {
+		parse(src).1.into_iter().map(|e| (e.pos.line, e.pos.column, e.message)).collect()
+	}
+
+	fn text(node: &Node) -> &str {
+		match node {
+			Node::Text(text) => text,
+			other => panic!("expected text, got {:?}", other),
+		}
+	}
+
+	fn var(cond: &Cond) -> &str {
+		match cond {
+			Cond::Var(name, _) => name,
+			other => panic!("expected a variable, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn double_brace_is_a_literal_brace() {
+		let nodes = parse_ok("a {{accent} b {{{{");
+		assert_eq!(nodes.len(), 1);
+		assert_eq!(text(&nodes[0]), "a {accent} b {{");
+	}
+
+	#[test]
+	fn brace_without_identifier_is_text() {
+		let nodes = parse_ok("x { y } {\"k\"}");
+		assert_eq!(nodes.len(), 1);
+		assert_eq!(text(&nodes[0]), "x { y } {\"k\"}");
+	}
+
+	#[test]
+	fn standalone_tag_removes_its_line() {
+		let nodes = parse_ok("a\n  {% if dark %}  \nb\n{% endif %}\nc\n");
+		assert_eq!(nodes.len(), 3);
+		assert_eq!(text(&nodes[0]), "a\n");
+		let Node::If(block) = &nodes[1] else { panic!("expected if, got {:?}", nodes[1]) };
+		assert_eq!(text(&block.branches[0].1[0]), "b\n");
+		assert_eq!(text(&nodes[2]), "c\n");
+	}
+
+	#[test]
+	fn inline_tag_keeps_its_line() {
+		let nodes = parse_ok("a {% if dark %}b{% endif %} c\n");
+		assert_eq!(nodes.len(), 3);
+		assert_eq!(text(&nodes[0]), "a ");
+		assert_eq!(text(&nodes[2]), " c\n");
+	}
+
+	#[test]
+	fn standalone_tag_at_end_of_input() {
+		let nodes = parse_ok("{% if dark %}\nx\n{% endif %}");
+		assert_eq!(nodes.len(), 1);
+		let Node::If(block) = &nodes[0] else { panic!("expected if, got {:?}", nodes[0]) };
+		assert_eq!(text(&block.branches[0].1[0]), "x\n");
+	}
+
+	#[test]
+	fn if_elif_else_branches() {
+		let nodes = parse_ok("{% if a %}1{% elif b %}2{% elif c %}3{% else %}4{% endif %}");
+		let Node::If(block) = &nodes[0] else { panic!("expected if, got {:?}", nodes[0]) };
+		let names: Vec<&str> = block.branches.iter().map(|(cond, _)| var(cond)).collect();
+		assert_eq!(names, ["a", "b", "c"]);
+		let bodies: Vec<&str> = block.branches.iter().map(|(_, body)| text(&body[0])).collect();
+		assert_eq!(bodies, ["1", "2", "3"]);
+		assert_eq!(text(&block.otherwise[0]), "4");
+	}
+
+	#[test]
+	fn nested_if_belongs_to_its_branch() {
+		let nodes = parse_ok("{% if a %}{% if b %}x{% else %}y{% endif %}{% else %}z{% endif %}");
+		assert_eq!(nodes.len(), 1);
+		let Node::If(outer) = &nodes[0] else { panic!("expected if, got {:?}", nodes[0]) };
+		assert_eq!(outer.branches.len(), 1);
+		assert_eq!(text(&outer.otherwise[0]), "z");
+		let Node::If(inner) = &outer.branches[0].1[0] else { panic!("expected nested if") };
+		assert_eq!(var(&inner.branches[0].0), "b");
+		assert_eq!(text(&inner.branches[0].1[0]), "x");
+		assert_eq!(text(&inner.otherwise[0]), "y");
+	}
+
+	#[test]
+	fn elif_after_else_is_an_error() {
+		assert_eq!(
+			errors("{% if a %}{% else %}{% elif b %}{% endif %}"),
+			[(1, 21, "unexpected `{% elif %}` after `{% else %}`".to_string())]
+		);
+	}
+
+	#[test]
+	fn unclosed_blocks_point_at_the_opening_tag() {
+		assert_eq!(
+			errors("x\n  {% for c in colors %}\n{% if a %}\n"),
+			[
+				(3, 1, "`{% if %}` without matching `{% endif %}`".to_string()),
+				(2, 3, "`{% for %}` without matching `{% endfor %}`".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn error_positions() {
+		assert_eq!(errors("{% endif %}"), [(1, 1, "unexpected `{% endif %}`".to_string())]);
+		assert_eq!(errors("ab\ncd {% bogus %}"), [(2, 4, "unknown tag `bogus`".to_string())]);
+		assert_eq!(
+			errors("a\n\tb {% for x of y %}{% endfor %}"),
+			[(2, 11, "expected `{% for <name> in <list> %}`".to_string())]
+		);
+		assert_eq!(
+			errors("{% if a %}\n{% endif a %}"),
+			[(2, 10, "`{% endif %}` takes no arguments".to_string())]
+		);
+		assert_eq!(
+			errors("{% if a\n%}"),
+			[(1, 1, "unterminated tag (expected `%}` on the same line)".to_string())]
+		);
+		assert_eq!(
+			errors("a: {accent | bogus 1}"),
+			[(1, 14, "unknown filter `bogus`".to_string())]
+		);
+		assert_eq!(
+			errors("a\nb {accent\n"),
+			[(2, 3, "unterminated token (write `{{` for a literal brace)".to_string())]
+		);
+	}
+
+	#[test]
+	fn parsing_continues_after_errors() {
+		let src = "{% bogus %}\n{% if a b %}x{% endif %}\n{% endfor %}\n";
+		let lines: Vec<usize> = errors(src).into_iter().map(|(line, _, _)| line).collect();
+		assert_eq!(lines, [1, 2, 3]);
+	}
+}
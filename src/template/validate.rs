@@ -0,0 +1,150 @@
+use super::parser::{Attr, Cond, Error, Expr, Node, Operand, Pos};
+use super::Context;
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+// Reports every problem `render` could run into, in all branches and loops,
+// instead of stopping at the first one.
+pub fn check(nodes: &[Node], ctx: &Context) -> Vec<Error> {
+	let mut checker = Checker { ctx, locals: Vec::new(), errors: Vec::new() };
+	checker.nodes(nodes);
+	checker.errors
+}
+
+struct Checker<'a> {
+	ctx: &'a Context,
+	locals: Vec<&'a str>,
+	errors: Vec<Error>,
+}
+
+impl<'a> Checker<'a> {
+	fn nodes(&mut self, nodes: &'a [Node]) {
+		for node in nodes {
+			match node {
+				Node::Text(_) => {}
+				Node::Expr(expr) => self.expr(expr),
+				Node::If(block) => {
+					for (cond, body) in &block.branches {
+						self.cond(cond);
+						self.nodes(body);
+					}
+					self.nodes(&block.otherwise);
+				}
+				Node::For(block) => {
+					// An empty list name means the header didn't parse, which the
+					// parser has already reported.
+					if !block.list.is_empty() && !self.ctx.lists.contains_key(block.list.as_str()) {
+						self.error(block.pos, format!("unknown list `{}`", block.list));
+					}
+					self.locals.push(&block.var);
+					self.nodes(&block.body);
+					self.locals.pop();
+				}
+				Node::Include(include) => {
+					let start = self.errors.len();
+					self.nodes(&include.nodes);
+					for error in &mut self.errors[start..] {
+						error.file.get_or_insert_with(|| include.file.clone());
+					}
+				}
+			}
+		}
+	}
+
+	fn expr(&mut self, expr: &Expr) {
+		let local = self.locals.contains(&expr.name.as_str());
+		if let Some(attr) = expr.attr {
+			if !local {
+				let attr = if attr == Attr::Index { "index" } else { "name" };
+				self.error(expr.pos, format!("`.{}` is only available on loop variables", attr));
+			}
+			return;
+		}
+
+		if !local && self.ctx.text.contains_key(&expr.name) {
+			if expr.format.is_some() || !expr.filters.is_empty() {
+				self.error(
+					expr.pos,
+					format!("`{}` is not a color; modifiers and filters only apply to colors", expr.name),
+				);
+			}
+			return;
+		}
+
+		self.color(&expr.name, expr.pos);
+		for filter in &expr.filters {
+			for name in filter.refs() {
+				self.color(name, expr.pos);
+			}
+		}
+	}
+
+	fn cond(&mut self, cond: &Cond) {
+		match cond {
+			// Already reported by the parser.
+			Cond::Invalid => {}
+			Cond::Var(name, pos) => self.var(name, *pos),
+			Cond::Eq(left, right, pos) => {
+				for operand in [left, right] {
+					if let Operand::Var(name) = operand {
+						self.var(name, *pos);
+					}
+				}
+			}
+			Cond::Not(inner) => self.cond(inner),
+			Cond::And(a, b) | Cond::Or(a, b) => {
+				self.cond(a);
+				self.cond(b);
+			}
+		}
+	}
+
+	fn var(&mut self, name: &str, pos: Pos) {
+		if !self.ctx.text.contains_key(name) {
+			self.color(name, pos);
+		}
+	}
+
+	fn color(&mut self, name: &str, pos: Pos) {
+		if !self.locals.contains(&name) && !self.ctx.colors.contains_key(name) {
+			self.error(pos, format!("unknown variable `{}`", name));
+		}
+	}
+
+	fn error(&mut self, pos: Pos, message: String) {
+		self.errors.push(Error::new(pos, message));
+	}
+}
+
+// Checks that `output` can be written: it is not a directory, an existing
+// file is writable, and the nearest existing directory accepts new files.
+pub fn check_output(output: &Path) -> Result<(), String> {
+	if output.is_dir() {
+		return Err(format!("Output path is a directory: {}", output.display()));
+	}
+	if output.exists() {
+		OpenOptions::new()
+			.append(true)
+			.open(output)
+			.map_err(|e| format!("Output file is not writable: {} ({})", output.display(), e))?;
+	}
+
+	let dir = output
+		.ancestors()
+		.skip(1)
+		.map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+		.find(|dir| dir.exists())
+		.unwrap_or(Path::new("/"));
+	if !dir.is_dir() {
+		return Err(format!("Not a directory: {}", dir.display()));
+	}
+
+	let probe = dir.join(format!(".tinte-validate-{}", std::process::id()));
+	match fs::File::create(&probe) {
+		Ok(_) => {
+			let _ = fs::remove_file(&probe);
+			Ok(())
+		}
+		Err(e) => Err(format!("Output directory is not writable: {} ({})", dir.display(), e)),
+	}
+}
@@ -0,0 +1,64 @@
+use crate::color::{Palette, Rgb};
+use crate::config::expand_path;
+use crate::output;
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::PathBuf;
+
+// OSC escape sequences that recolor a running terminal: the 256 indexed
+// colors (4), foreground (10), background (11), cursor (12), selection
+// (17, 19) and the urxvt border (708).
+pub fn sequences(palette: &Palette) -> String {
+	let osc = |code: &str, color: Rgb| format!("\x1b]{};{}\x1b\\", code, color.to_hex());
+
+	let mut out = String::new();
+	for (i, color) in palette.colors.iter().chain(palette.extended().iter()).enumerate() {
+		out.push_str(&osc(&format!("4;{}", i), *color));
+	}
+	out.push_str(&osc("10", palette.foreground()));
+	out.push_str(&osc("11", palette.background()));
+	out.push_str(&osc("12", palette.cursor));
+	out.push_str(&osc("17", palette.selection_bg));
+	out.push_str(&osc("19", palette.selection_fg));
+	out.push_str(&osc("708", palette.background()));
+	out
+}
+
+// Sends `sequences` to every terminal of the current user, returning the
+// ttys written. Terminals that can't be written are skipped.
+pub fn apply(sequences: &str) -> Result<Vec<PathBuf>> {
+	let uid = fs::metadata("/proc/self").context("Failed to determine the current user")?.uid();
+	let entries = fs::read_dir("/dev/pts").context("Failed to list /dev/pts")?;
+
+	let mut applied = Vec::new();
+	for entry in entries.flatten() {
+		let path = entry.path();
+		let owned = fs::metadata(&path).is_ok_and(|meta| meta.file_type().is_char_device() && meta.uid() == uid);
+		if !owned || path.file_name().is_some_and(|name| name == "ptmx") {
+			continue;
+		}
+		let written = OpenOptions::new()
+			.write(true)
+			.open(&path)
+			.and_then(|mut tty| tty.write_all(sequences.as_bytes()));
+		if written.is_ok() {
+			applied.push(path);
+		}
+	}
+	applied.sort();
+	Ok(applied)
+}
+
+// Saves `sequences` as `~/.cache/tinte/sequences`, for shells to `cat` on
+// startup so new terminals pick up the colors too.
+pub fn save(sequences: &str) -> Result<PathBuf> {
+	let dir = dirs::cache_dir()
+		.unwrap_or_else(|| expand_path("~/.cache"))
+		.join("tinte");
+	fs::create_dir_all(&dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+	let path = dir.join("sequences");
+	output::write_atomic(&path, sequences.as_bytes())?;
+	Ok(path)
+}
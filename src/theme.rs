@@ -0,0 +1,80 @@
+use crate::color::{Palette, Rgb};
+use crate::extraction::{extract_palette, generate_from_color};
+use crate::preset;
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub enum Source {
+	Image(PathBuf),
+	Color(Rgb),
+	Preset(String),
+}
+
+impl fmt::Display for Source {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Source::Image(path) => write!(f, "{}", path.display()),
+			Source::Color(color) => write!(f, "{}", color),
+			Source::Preset(name) => write!(f, "{}", name),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generator {
+	Chromatic,
+	Monochrome,
+	Subtle,
+	Seed,
+	Preset,
+}
+
+impl Generator {
+	pub fn name(self) -> &'static str {
+		match self {
+			Generator::Chromatic => "chromatic",
+			Generator::Monochrome => "monochrome",
+			Generator::Subtle => "subtle",
+			Generator::Seed => "seed",
+			Generator::Preset => "preset",
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+	pub palette: Palette,
+	pub light_mode: bool,
+	pub source: Source,
+	pub generator: Generator,
+}
+
+impl Theme {
+	// Generates the palette for `source` in the requested mode. A preset
+	// without that variant falls back to the one it has, and `light_mode`
+	// reflects the mode actually used.
+	pub fn generate(source: Source, light_mode: bool) -> Result<Self> {
+		let (palette, light_mode, generator) = match &source {
+			Source::Image(path) => {
+				let (palette, generator) = extract_palette(path, light_mode)?;
+				(palette, light_mode, generator)
+			}
+			Source::Color(color) => (generate_from_color(*color, light_mode), light_mode, Generator::Seed),
+			Source::Preset(name) => {
+				let preset = preset::find(name)
+					.ok_or_else(|| anyhow!("Unknown preset: {} (see `tinte preset --list`)", name))?;
+				match preset.palette(light_mode) {
+					Some(palette) => (palette, light_mode, Generator::Preset),
+					None => (preset.palette_or_fallback(light_mode), !light_mode, Generator::Preset),
+				}
+			}
+		};
+		Ok(Self { palette, light_mode, source, generator })
+	}
+
+	pub fn mode(&self) -> &'static str {
+		if self.light_mode { "light" } else { "dark" }
+	}
+}
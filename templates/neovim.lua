@@ -7,24 +7,24 @@ return {
 			disable_italics = false,
 			colors = {
 				-- Monotone shades (base00-base07)
-				base00 = "{background}", -- Default background
-				base01 = "{color8}", -- Lighter background (status bars)
-				base02 = "{color0}", -- Selection background
-				base03 = "{color11}", -- Comments, invisibles
-				base04 = "{color7}", -- Dark foreground
-				base05 = "{foreground}", -- Default foreground
-				base06 = "{color15}", -- Light foreground
-				base07 = "{color7}", -- Light background
+				base00 = "{base00}", -- Default background
+				base01 = "{base01}", -- Lighter background (status bars)
+				base02 = "{base02}", -- Selection background
+				base03 = "{base03}", -- Comments, invisibles
+				base04 = "{base04}", -- Dark foreground
+				base05 = "{base05}", -- Default foreground
+				base06 = "{base06}", -- Light foreground
+				base07 = "{base07}", -- Light background
 
 				-- Accent colors (base08-base0F)
-				base08 = "{color1}", -- Variables, errors, red
-				base09 = "{color9}", -- Integers, constants, orange
-				base0A = "{color3}", -- Classes, types, yellow
-				base0B = "{color2}", -- Strings, green
-				base0C = "{color6}", -- Support, regex, cyan
-				base0D = "{color4}", -- Functions, keywords, blue
-				base0E = "{color5}", -- Keywords, storage, magenta
-				base0F = "{color11}", -- Deprecated, brown/yellow
+				base08 = "{base08}", -- Variables, errors, red
+				base09 = "{base09}", -- Integers, constants, orange
+				base0A = "{base0A}", -- Classes, types, yellow
+				base0B = "{base0B}", -- Strings, green
+				base0C = "{base0C}", -- Support, regex, cyan
+				base0D = "{base0D}", -- Functions, keywords, blue
+				base0E = "{base0E}", -- Keywords, storage, magenta
+				base0F = "{base0F}", -- Deprecated, brown/yellow
 			},
 		},
 		config = function(_, opts)